@@ -0,0 +1,74 @@
+//! Generates one test per (day, input, part) for every `src/inputs/aocN[s[K]].in`
+//! that has a `.ans` sidecar with `p1: <answer>` and/or `p2: <answer>` lines.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+const INPUTS_DIR: &str = "src/inputs";
+
+/// Day number of an input named `aocN.in`, `aocNs.in` or `aocNsK.in`.
+fn day_of(stem: &str) -> Option<usize> {
+    let rest = stem.strip_prefix("aoc")?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (day, suffix) = rest.split_at(digits);
+    let sample = suffix
+        .strip_prefix('s')
+        .is_some_and(|k| k.chars().all(|c| c.is_ascii_digit()));
+    if !suffix.is_empty() && !sample {
+        return None;
+    }
+    day.parse().ok()
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={INPUTS_DIR}");
+    let mut inputs = fs::read_dir(INPUTS_DIR)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "in"))
+        .filter_map(|p| {
+            let stem = p.file_stem()?.to_str()?.to_string();
+            Some((day_of(&stem)?, stem))
+        })
+        .collect::<Vec<_>>();
+    inputs.sort();
+
+    let mut out = String::new();
+    let mut current = None;
+    for (day, stem) in inputs {
+        let ans = Path::new(INPUTS_DIR).join(format!("{stem}.ans"));
+        let Ok(answers) = fs::read_to_string(&ans) else {
+            println!("cargo:warning={stem}.in has no {stem}.ans, not tested");
+            continue;
+        };
+        if current != Some(day) {
+            if current.is_some() {
+                out.push_str("}\n\n");
+            }
+            writeln!(out, "mod day{day} {{").unwrap();
+            current = Some(day);
+        }
+        for line in answers.lines().filter(|l| !l.trim().is_empty()) {
+            let (part, expected) = line
+                .split_once(':')
+                .and_then(|(p, e)| Some((p.trim().strip_prefix('p')?.parse::<usize>().ok()?, e)))
+                .filter(|(p, _)| (1..=2).contains(p))
+                .unwrap_or_else(|| panic!("{}: bad answer line {line:?}", ans.display()));
+            writeln!(
+                out,
+                "    #[test]\n    fn {stem}_p{part}() {{\n        super::check({day}, \"{stem}.in\", {part}, {:?});\n    }}",
+                expected.trim()
+            )
+            .unwrap();
+        }
+    }
+    if current.is_some() {
+        out.push_str("}\n");
+    }
+    fs::write(
+        Path::new(&env::var("OUT_DIR").unwrap()).join("samples.rs"),
+        out,
+    )
+    .unwrap();
+}
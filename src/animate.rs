@@ -0,0 +1,129 @@
+use std::{
+    fmt::Write as _,
+    io::{self, IsTerminal, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{grid::Grid, render::Rgb};
+
+/// One character of a frame and the color to draw it in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Rgb,
+}
+
+impl Cell {
+    pub fn new(ch: char, color: Rgb) -> Self {
+        Self { ch, color }
+    }
+}
+
+/// Receiver of solver frames. `draw` is only called if the frame is actually shown,
+/// so with [`NoAnimation`] the hook compiles down to nothing.
+pub trait Animate {
+    fn frame(&mut self, draw: impl FnOnce() -> Grid<Cell>);
+
+    /// Whether frames are shown at all, to skip bookkeeping that only feeds them.
+    fn is_on(&self) -> bool {
+        true
+    }
+}
+
+/// Animation disabled; what every solver uses outside of `run --animate`.
+pub struct NoAnimation;
+
+impl Animate for NoAnimation {
+    #[inline(always)]
+    fn frame(&mut self, _draw: impl FnOnce() -> Grid<Cell>) {}
+
+    #[inline(always)]
+    fn is_on(&self) -> bool {
+        false
+    }
+}
+
+/// Plays frames on stdout: redrawn in place with 24-bit ANSI colors on a terminal,
+/// as plain text frames separated by blank lines otherwise.
+pub struct Terminal {
+    delay: Duration,
+    ansi: bool,
+    frames: usize,
+}
+
+impl Terminal {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            ansi: io::stdout().is_terminal(),
+            frames: 0,
+        }
+    }
+
+    fn draw(&self, g: &Grid<Cell>) -> String {
+        let mut r = String::new();
+        if self.ansi {
+            // cursor home and clear screen
+            r.push_str("\x1b[H\x1b[2J");
+        } else if self.frames > 0 {
+            r.push('\n');
+        }
+        g.rows().for_each(|row| {
+            let mut color = None;
+            row.iter().for_each(|c| {
+                if self.ansi && color != Some(c.color) {
+                    let Rgb(red, green, blue) = c.color;
+                    write!(r, "\x1b[38;2;{red};{green};{blue}m").unwrap();
+                    color = Some(c.color);
+                }
+                r.push(c.ch);
+            });
+            if self.ansi {
+                r.push_str("\x1b[0m");
+            }
+            r.push('\n');
+        });
+        r
+    }
+}
+
+impl Animate for Terminal {
+    fn frame(&mut self, draw: impl FnOnce() -> Grid<Cell>) {
+        let s = self.draw(&draw());
+        let mut out = io::stdout().lock();
+        // a closed pipe just ends the show
+        if out
+            .write_all(s.as_bytes())
+            .and_then(|_| out.flush())
+            .is_ok()
+        {
+            self.frames += 1;
+            thread::sleep(self.delay);
+        }
+    }
+}
+
+#[test]
+fn test_animate() {
+    let g = Grid::parse("ab\nc.\n", |c| Cell::new(c, Rgb::WHITE));
+    let mut t = Terminal {
+        delay: Duration::ZERO,
+        ansi: false,
+        frames: 0,
+    };
+    assert_eq!(t.draw(&g), "ab\nc.\n");
+    t.frames = 1;
+    assert_eq!(t.draw(&g), "\nab\nc.\n");
+    t.ansi = true;
+    assert_eq!(
+        t.draw(&g),
+        "\x1b[H\x1b[2J\x1b[38;2;255;255;255mab\x1b[0m\n\x1b[38;2;255;255;255mc.\x1b[0m\n"
+    );
+    let mut called = false;
+    NoAnimation.frame(|| {
+        called = true;
+        g.clone()
+    });
+    assert!(!called && !NoAnimation.is_on() && t.is_on());
+}
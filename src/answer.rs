@@ -0,0 +1,141 @@
+use std::fmt;
+
+use crate::json;
+
+/// What a part's solver came up with.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+    /// Not solved (yet), and why.
+    Unsolved(String),
+}
+
+macro_rules! from_int {
+    ($variant:ident: $($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Self::$variant(n as _)
+            }
+        }
+    )*};
+}
+
+from_int!(Unsigned: u32, u64, usize);
+from_int!(Signed: i32, i64);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl Answer {
+    /// An expected answer as written in a `.ans` file: a number only if it's
+    /// spelled the way the number prints, so `0042` or `+42` stay text.
+    #[cfg(test)]
+    pub fn parse(s: &str) -> Self {
+        match (s.parse::<u64>(), s.parse::<i64>()) {
+            (Ok(n), _) if n.to_string() == s => Self::Unsigned(n),
+            (_, Ok(n)) if n.to_string() == s => Self::Signed(n),
+            _ => Self::Text(s.to_string()),
+        }
+    }
+
+    /// The value as JSON, numbers as numbers.
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Unsigned(n) => n.to_string(),
+            Self::Signed(n) => n.to_string(),
+            Self::Text(s) => json::string(s),
+            Self::Unsolved(why) => format!("{{\"unsolved\":{}}}", json::string(why)),
+        }
+    }
+
+    /// One-line form that [`Answer::decode`] reads back, tagged with the kind.
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Unsigned(n) => format!("u {n}"),
+            Self::Signed(n) => format!("i {n}"),
+            Self::Text(s) => format!("t {s}"),
+            Self::Unsolved(why) => format!("? {why}"),
+        }
+    }
+
+    pub fn decode(s: &str) -> Option<Self> {
+        let (kind, value) = s.split_once(' ')?;
+        match kind {
+            "u" => value.parse().ok().map(Self::Unsigned),
+            "i" => value.parse().ok().map(Self::Signed),
+            "t" => Some(Self::Text(value.to_string())),
+            "?" => Some(Self::Unsolved(value.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Numbers are equal by value whatever their signedness, never to text.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        use Answer::*;
+        match (self, other) {
+            (Unsigned(a), Unsigned(b)) => a == b,
+            (Signed(a), Signed(b)) => a == b,
+            (Unsigned(u), Signed(i)) | (Signed(i), Unsigned(u)) => *i as i128 == *u as i128,
+            (Text(a), Text(b)) | (Unsolved(a), Unsolved(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => write!(f, "{n}"),
+            Self::Signed(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+            Self::Unsolved(why) => write!(f, "unsolved ({why})"),
+        }
+    }
+}
+
+#[test]
+fn test_answer() {
+    assert_eq!(Answer::parse("42"), Answer::from(42usize));
+    assert_eq!(Answer::parse("-2"), Answer::from(-2i64));
+    assert_eq!(Answer::parse("114"), Answer::from(114i64));
+    assert_ne!(Answer::parse("0042"), Answer::from(42u32));
+    assert_ne!(Answer::parse("+42"), Answer::from(42u32));
+    assert_eq!(Answer::parse("0042"), Answer::from("0042"));
+    assert_ne!(Answer::from(u64::MAX), Answer::from(-1i64));
+
+    let answers = [
+        Answer::from(u64::MAX),
+        Answer::from(-7i64),
+        Answer::from("two words"),
+        Answer::Unsolved("needs part 1".to_string()),
+    ];
+    for a in &answers {
+        assert_eq!(Answer::decode(&a.encode()).as_ref(), Some(a));
+    }
+    assert_eq!(Answer::decode("1624"), None);
+    assert_eq!(
+        answers.iter().map(Answer::to_json).collect::<Vec<_>>(),
+        [
+            "18446744073709551615",
+            "-7",
+            "\"two words\"",
+            "{\"unsolved\":\"needs part 1\"}"
+        ]
+    );
+    assert_eq!(answers[3].to_string(), "unsolved (needs part 1)");
+}
@@ -0,0 +1,251 @@
+use core::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::geometry::{Direction, Point};
+
+pub const MAX_WIDTH: usize = u128::BITS as usize;
+
+/// Bit-packed 2D grid of booleans: one `u128` per row, bit `x` of row `y` is cell `(x, y)`.
+/// Bits beyond `width` are always kept clear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    rows: Vec<u128>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        if width > MAX_WIDTH {
+            panic!("(╯°□°）╯︵ ┻━┻ bit grid wider than {MAX_WIDTH}");
+        }
+        Self {
+            width,
+            height,
+            rows: vec![0; height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> bool) -> Self {
+        let mut r = Self::new(width, height);
+        (0..height)
+            .for_each(|y| r.rows[y] = (0..width).filter(|x| f(*x, y)).fold(0, |a, x| a | 1 << x));
+        r
+    }
+
+    /// Parses one row per line, setting the cells whose character satisfies `f`.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> bool) -> Self {
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+        let mut r = Self::new(width, 0);
+        s.lines().enumerate().for_each(|(y, l)| {
+            let (mut len, mut row) = (0, 0);
+            for c in l.chars() {
+                if len < width && f(c) {
+                    row |= 1 << len;
+                }
+                len += 1;
+            }
+            if len != width {
+                panic!("(ノ°Д°）ノ︵ ┻━┻ ragged grid row {y}");
+            }
+            r.rows.push(row);
+        });
+        r.height = r.rows.len();
+        r
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn mask(&self) -> u128 {
+        u128::MAX
+            .checked_shr((MAX_WIDTH - self.width) as u32)
+            .unwrap_or(0)
+    }
+
+    pub fn in_grid(&self, p: Point) -> bool {
+        p.x >= 0 && (p.x as usize) < self.width && p.y >= 0 && (p.y as usize) < self.height
+    }
+
+    /// Whether the cell at `p` is set; cells outside the grid are never set.
+    pub fn get(&self, p: Point) -> bool {
+        self.in_grid(p) && self.rows[p.y as usize] >> p.x & 1 != 0
+    }
+
+    /// Sets or clears the cell at `p`, returning its previous value.
+    pub fn set(&mut self, p: Point, value: bool) -> bool {
+        if !self.in_grid(p) {
+            panic!("(ノ°Д°）ノ︵ ┻━┻ {p:?} is outside the bit grid");
+        }
+        let prev = self.get(p);
+        let bit = 1 << p.x;
+        let row = &mut self.rows[p.y as usize];
+        if value {
+            *row |= bit;
+        } else {
+            *row &= !bit;
+        }
+        prev
+    }
+
+    pub fn row(&self, y: usize) -> u128 {
+        self.rows[y]
+    }
+
+    pub fn set_row(&mut self, y: usize, bits: u128) {
+        self.rows[y] = bits & self.mask();
+    }
+
+    /// Column `x` packed into an integer, bit `y` being cell `(x, y)`.
+    pub fn column(&self, x: usize) -> u128 {
+        self.rows
+            .iter()
+            .enumerate()
+            .fold(0, |a, (y, r)| a | (r >> x & 1) << y)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.rows.iter().map(|r| r.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|r| *r == 0)
+    }
+
+    /// Moves every set cell one step towards `dir`; cells pushed off the edge are dropped.
+    pub fn shift(&self, dir: Direction) -> Self {
+        let mut r = Self::new(self.width, self.height);
+        if self.height == 0 {
+            return r;
+        }
+        match dir {
+            Direction::Up => r.rows[..self.height - 1].copy_from_slice(&self.rows[1..]),
+            Direction::Down => r.rows[1..].copy_from_slice(&self.rows[..self.height - 1]),
+            Direction::Left => r
+                .rows
+                .iter_mut()
+                .zip(&self.rows)
+                .for_each(|(a, b)| *a = b >> 1),
+            Direction::Right => {
+                let mask = self.mask();
+                r.rows
+                    .iter_mut()
+                    .zip(&self.rows)
+                    .for_each(|(a, b)| *a = b << 1 & mask)
+            }
+        }
+        r
+    }
+
+    /// Coordinates of all set cells, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, r)| {
+            let mut bits = *r;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let x = bits.trailing_zeros();
+                bits &= bits - 1;
+                Some(Point::new(x as i32, y as i32))
+            })
+        })
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(u128, u128) -> u128) -> Self {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        Self {
+            width: self.width,
+            height: self.height,
+            rows: self
+                .rows
+                .iter()
+                .zip(&other.rows)
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a ^ b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mask = self.mask();
+        BitGrid {
+            width: self.width,
+            height: self.height,
+            rows: self.rows.iter().map(|r| !r & mask).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.rows.iter().try_for_each(|r| {
+            (0..self.width)
+                .try_for_each(|x| write!(f, "{}", if r >> x & 1 != 0 { '#' } else { '.' }))?;
+            writeln!(f)
+        })
+    }
+}
+
+#[test]
+fn test_bitgrid() {
+    let g = BitGrid::parse("#..\n.#.\n..#\n##.\n", |c| c == '#');
+    assert_eq!((g.width(), g.height()), (3, 4));
+    assert_eq!(g.count_ones(), 5);
+    assert!(g.get(Point::new(1, 1)) && !g.get(Point::new(1, 0)) && !g.get(Point::new(-1, 0)));
+    assert_eq!((g.row(3), g.column(0)), (0b011, 0b1001));
+    assert_eq!(
+        g.shift(Direction::Right).to_string(),
+        ".#.\n..#\n...\n.##\n"
+    );
+    assert_eq!(g.shift(Direction::Left).to_string(), "...\n#..\n.#.\n#..\n");
+    assert_eq!(g.shift(Direction::Up).to_string(), ".#.\n..#\n##.\n...\n");
+    assert_eq!(g.shift(Direction::Down).to_string(), "...\n#..\n.#.\n..#\n");
+    assert_eq!((!&g).count_ones(), 7);
+    assert_eq!((&g & &!&g).count_ones(), 0);
+    assert_eq!((&g | &g.shift(Direction::Down)).count_ones(), 8);
+    assert!((&g ^ &g).is_empty());
+    assert_eq!(
+        g.points().collect::<Vec<_>>(),
+        [(0, 0), (1, 1), (2, 2), (0, 3), (1, 3)].map(|(x, y)| Point::new(x, y))
+    );
+    let mut h = BitGrid::new(2, 2);
+    assert!(!h.set(Point::new(1, 0), true));
+    assert!(h.set(Point::new(1, 0), false));
+    assert!(h.is_empty());
+    let outside = |p| std::panic::catch_unwind(|| BitGrid::new(2, 2).set(p, true)).is_err();
+    assert!(outside(Point::new(2, 0)) && outside(Point::new(0, -1)) && outside(Point::new(0, 2)));
+    assert!(std::panic::catch_unwind(|| BitGrid::parse("##\n#\n", |c| c == '#')).is_err());
+}
@@ -0,0 +1,121 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::answer::Answer;
+
+/// Bumped whenever a module shared by the days (`grid`, `math`, `search`, `bitgrid`,
+/// `parse`, `interval`, ...) changes in a way that could change answers, since no
+/// day's own `VERSION` would.
+pub const EPOCH: u32 = 1;
+
+/// Everything an answer depends on besides [`EPOCH`] and the crate version, which
+/// every key shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key<'a> {
+    pub day: usize,
+    pub part: usize,
+    pub variant: &'a str,
+    pub input_hash: u64,
+    /// The day's `VERSION`, bumped whenever its solver changes.
+    pub version: u32,
+}
+
+/// 64-bit FNV-1a, stable across builds and platforms unlike `DefaultHasher`.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Answers on disk, one small file per key, so parallel days never share a file.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, k: &Key) -> PathBuf {
+        self.dir.join(format!(
+            "day{}-p{}-{}-{:016x}-v{}-e{EPOCH}-{}",
+            k.day,
+            k.part,
+            k.variant,
+            k.input_hash,
+            k.version,
+            env!("CARGO_PKG_VERSION")
+        ))
+    }
+
+    /// The cached answer, if any; an unreadable entry counts as a miss.
+    pub fn get(&self, key: &Key) -> Option<Answer> {
+        Answer::decode(&fs::read_to_string(self.path(key)).ok()?)
+    }
+
+    /// Stores `answer`, written to a temporary file first so readers never see
+    /// half an answer.
+    pub fn put(&self, key: &Key, answer: &Answer) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        let tmp = path.with_extension(format!("tmp{}", process::id()));
+        fs::write(&tmp, answer.encode())?;
+        fs::rename(tmp, path)
+    }
+
+    /// The cached answer for `key`, or the one from `solve`, which is then cached
+    /// unless it's [`Answer::Unsolved`], so it's retried on the next run.
+    pub fn get_or_solve(&self, key: &Key, solve: impl FnOnce() -> Answer) -> Answer {
+        if let Some(answer) = self.get(key) {
+            return answer;
+        }
+        let answer = solve();
+        if let Answer::Unsolved(_) = answer {
+            return answer;
+        }
+        if let Err(e) = self.put(key, &answer) {
+            eprintln!("warning: can't cache day{} p{}: {e}", key.day, key.part);
+        }
+        answer
+    }
+}
+
+#[test]
+fn test_cache() {
+    assert_eq!(
+        (hash(""), hash("a")),
+        (0xcbf29ce484222325, 0xaf63dc4c8601ec8c)
+    );
+
+    let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", process::id()));
+    let cache = Cache::new(&dir);
+    let key = Key {
+        day: 1,
+        part: 2,
+        variant: "default",
+        input_hash: hash("input"),
+        version: 1,
+    };
+    assert_eq!(cache.get(&key), None);
+    assert_eq!(cache.get_or_solve(&key, || 42u64.into()), 42u64.into());
+    assert_eq!(cache.get_or_solve(&key, || unreachable!()), 42u64.into());
+    fs::write(cache.path(&key), "42").unwrap();
+    assert_eq!(cache.get(&key), None);
+    let unsolved = Key { part: 1, ..key };
+    let todo = || Answer::Unsolved("not started".to_string());
+    assert_eq!(cache.get_or_solve(&unsolved, todo), todo());
+    assert_eq!(cache.get(&unsolved), None);
+    let stale = Key { version: 2, ..key };
+    let other = Key {
+        variant: "brute",
+        ..key
+    };
+    assert_eq!((cache.get(&stale), cache.get(&other)), (None, None));
+    fs::remove_dir_all(dir).unwrap();
+}
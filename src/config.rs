@@ -0,0 +1,312 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{input, opt};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+/// Runner settings, merged from the built-in defaults, the user's `aoc.toml`, the
+/// project's `aoc.toml` and command line flags, later ones winning. Relative paths
+/// in the project's file are relative to the directory it's in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub year: u32,
+    /// Directory with the `aocN.in` puzzle inputs.
+    pub inputs: String,
+    /// Rayon worker threads, 0 for one per core.
+    pub threads: usize,
+    /// Where the session token comes from: `env:VAR` or `file:PATH`.
+    pub session: String,
+    /// Directory of cached answers.
+    pub cache: String,
+    /// How `run` prints answers: `text` or `json`.
+    pub run_format: String,
+    pub render_out: String,
+    pub render_format: String,
+    pub render_scale: usize,
+    pub serve_port: u16,
+    /// Solving time limit per `serve` request, in seconds.
+    pub serve_timeout: u64,
+    /// Files and flags the values were taken from, in merge order.
+    pub sources: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: 2023,
+            inputs: input::INPUTS_DIR.to_string(),
+            threads: 0,
+            session: "env:AOC_SESSION".to_string(),
+            cache: ".aoc-cache".to_string(),
+            run_format: "text".to_string(),
+            render_out: "render".to_string(),
+            render_format: "png".to_string(),
+            render_scale: 4,
+            serve_port: 8023,
+            serve_timeout: 10,
+            sources: vec!["defaults".to_string()],
+        }
+    }
+}
+
+/// Keys holding paths, which are relative to the project file's directory.
+const PATH_KEYS: [&str; 3] = ["inputs", "cache", "render.out"];
+
+/// Command line flags overriding a config key.
+const FLAGS: [(&str, &str); 10] = [
+    ("--year", "year"),
+    ("--inputs", "inputs"),
+    ("--threads", "threads"),
+    ("--session", "session"),
+    ("--output", "run.format"),
+    ("--out", "render.out"),
+    ("--format", "render.format"),
+    ("--scale", "render.scale"),
+    ("--port", "serve.port"),
+    ("--timeout", "serve.timeout"),
+];
+
+/// `line` without its `#` comment, if any; a `#` inside a string doesn't count.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let end = line
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                in_string = !in_string;
+            }
+            *c == '#' && !in_string
+        })
+        .map_or(line.len(), |(i, _)| i);
+    line[..end].trim()
+}
+
+/// `key = value` pairs of a TOML subset: `[section]` headers, which prefix the
+/// keys below them as `section.key`, double-quoted strings, integers and `#`
+/// comments. Strings are returned unquoted.
+fn parse(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut section = String::new();
+    let mut r = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let err = |msg: &str| Err(format!("line {}: {msg}", i + 1));
+        let line = strip_comment(line);
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[') {
+            let Some(name) = name.strip_suffix(']') else {
+                return err("unclosed section header");
+            };
+            section = format!("{}.", name.trim());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return err("expected `key = value`");
+        };
+        let value = value.trim();
+        let value = if let Some(s) = value.strip_prefix('"') {
+            match s.strip_suffix('"') {
+                Some(s) if !s.contains('"') => s.to_string(),
+                _ => return err("bad string"),
+            }
+        } else if value.parse::<i64>().is_ok() {
+            value.to_string()
+        } else {
+            return err("values must be quoted strings or integers");
+        };
+        r.push((format!("{section}{}", key.trim()), value));
+    }
+    Ok(r)
+}
+
+fn number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{key} must be a non-negative integer, got {value:?}"))
+}
+
+impl Config {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "year" => self.year = number(key, value)?,
+            "inputs" => self.inputs = value.to_string(),
+            "threads" => self.threads = number(key, value)?,
+            "session" if value.starts_with("env:") || value.starts_with("file:") => {
+                self.session = value.to_string()
+            }
+            "session" => {
+                return Err(format!(
+                    "session must be env:VAR or file:PATH, got {value:?}"
+                ))
+            }
+            "cache" => self.cache = value.to_string(),
+            "run.format" if ["text", "json"].contains(&value) => {
+                self.run_format = value.to_string()
+            }
+            "run.format" => return Err(format!("run.format must be text or json, got {value:?}")),
+            "render.out" => self.render_out = value.to_string(),
+            "render.format" if ["png", "ppm", "svg"].contains(&value) => {
+                self.render_format = value.to_string()
+            }
+            "render.format" => {
+                return Err(format!(
+                    "render.format must be png, ppm or svg, got {value:?}"
+                ))
+            }
+            "render.scale" => self.render_scale = number(key, value)?,
+            "serve.port" => self.serve_port = number(key, value)?,
+            "serve.timeout" => self.serve_timeout = number(key, value)?,
+            _ => return Err(format!("unknown key {key}")),
+        }
+        Ok(())
+    }
+
+    /// Merges in the settings from TOML `text`, read from `source`, with relative
+    /// paths taken from `base` if given.
+    fn merge(&mut self, source: &str, text: &str, base: Option<&Path>) -> Result<(), String> {
+        let resolve = |key: &str, value: &str| match base {
+            Some(base) if PATH_KEYS.contains(&key) => base.join(value).display().to_string(),
+            _ => value.to_string(),
+        };
+        parse(text)
+            .and_then(|pairs| {
+                pairs
+                    .iter()
+                    .try_for_each(|(k, v)| self.set(k, &resolve(k, v)))
+            })
+            .map_err(|e| format!("{source}: {e}"))?;
+        self.sources.push(source.to_string());
+        Ok(())
+    }
+
+    /// The user-wide config, `$XDG_CONFIG_HOME/aoc/aoc.toml` or `~/.config/aoc/aoc.toml`.
+    fn user_file() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+            .map(|dir| dir.join("aoc").join(FILE_NAME))
+    }
+
+    /// The project's `aoc.toml`, in the current directory or the nearest parent
+    /// that has one, relative to the current directory if it's in it.
+    fn project_file() -> Option<PathBuf> {
+        let cwd = env::current_dir().ok()?;
+        let path = cwd
+            .ancestors()
+            .map(|dir| dir.join(FILE_NAME))
+            .find(|p| p.is_file())?;
+        Some(
+            path.strip_prefix(&cwd)
+                .map_or(path.clone(), Path::to_path_buf),
+        )
+    }
+
+    /// Effective configuration for a command line: `--config FILE` replaces the
+    /// project's `aoc.toml`, and the flags in [`FLAGS`] override single keys.
+    pub fn load(args: &[String]) -> Result<Self, String> {
+        let mut c = Self::default();
+        if let Some(path) = Self::user_file() {
+            if let Ok(text) = fs::read_to_string(&path) {
+                c.merge(&path.display().to_string(), &text, None)?;
+            }
+        }
+        let explicit = opt(args, "--config").map(PathBuf::from);
+        if let Some(project) = explicit.clone().or_else(Self::project_file) {
+            let source = project.display().to_string();
+            match fs::read_to_string(&project) {
+                Ok(text) => c.merge(&source, &text, project.parent())?,
+                Err(e) if explicit.is_some() => return Err(format!("can't read {source}: {e}")),
+                Err(_) => {}
+            }
+        }
+        let mut overridden = false;
+        for (flag, key) in FLAGS {
+            if let Some(value) = opt(args, flag) {
+                c.set(key, value).map_err(|e| format!("{flag}: {e}"))?;
+                overridden = true;
+            }
+        }
+        if overridden {
+            c.sources.push("command line".to_string());
+        }
+        Ok(c)
+    }
+}
+
+/// The configuration as `aoc.toml` text.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# merged from: {}", self.sources.join(", "))?;
+        writeln!(f, "year = {}", self.year)?;
+        writeln!(f, "inputs = \"{}\"", self.inputs)?;
+        writeln!(f, "threads = {}", self.threads)?;
+        writeln!(f, "session = \"{}\"", self.session)?;
+        writeln!(f, "cache = \"{}\"", self.cache)?;
+        writeln!(f, "\n[run]")?;
+        writeln!(f, "format = \"{}\"", self.run_format)?;
+        writeln!(f, "\n[render]")?;
+        writeln!(f, "out = \"{}\"", self.render_out)?;
+        writeln!(f, "format = \"{}\"", self.render_format)?;
+        writeln!(f, "scale = {}", self.render_scale)?;
+        writeln!(f, "\n[serve]")?;
+        writeln!(f, "port = {}", self.serve_port)?;
+        writeln!(f, "timeout = {}", self.serve_timeout)
+    }
+}
+
+#[test]
+fn test_config() {
+    let mut c = Config::default();
+    c.merge(
+        "aoc.toml",
+        "# project settings\nthreads = 4 # per machine\ninputs = \"data/#2023\"\n\n[render]\nformat = \"svg\"\n",
+        None,
+    )
+    .unwrap();
+    assert_eq!((c.threads, c.inputs.as_str()), (4, "data/#2023"));
+    assert_eq!((c.render_format.as_str(), c.render_scale), ("svg", 4));
+    assert_eq!(c.sources, ["defaults", "aoc.toml"]);
+
+    let mut round_trip = Config::default();
+    round_trip.merge("aoc.toml", &c.to_string(), None).unwrap();
+    assert_eq!(round_trip.sources.len(), 2);
+    round_trip.sources = c.sources.clone();
+    assert_eq!(round_trip, c);
+
+    let mut nested = Config::default();
+    let text = "cache = \"/tmp/aoc\"\n[run]\nformat = \"json\"\n[render]\nout = \"img\"\n";
+    nested
+        .merge("../aoc.toml", text, Some(Path::new("..")))
+        .unwrap();
+    assert_eq!(
+        (nested.cache, nested.render_out, nested.run_format),
+        (
+            "/tmp/aoc".to_string(),
+            "../img".to_string(),
+            "json".to_string()
+        )
+    );
+
+    let e = |text| Config::default().merge("x.toml", text, None).unwrap_err();
+    assert_eq!(e("\n[serve\n"), "x.toml: line 2: unclosed section header");
+    assert_eq!(
+        e("threads = many"),
+        "x.toml: line 1: values must be quoted strings or integers"
+    );
+    assert_eq!(
+        e("[serve]\nthreads = 2"),
+        "x.toml: unknown key serve.threads"
+    );
+    assert!(e("[run]\nformat = \"yaml\"").contains("text or json"));
+    assert!(e("session = \"hunter2\"").contains("env:VAR or file:PATH"));
+
+    let args = ["run", "--year", "2022", "--session", "file:token.txt"].map(String::from);
+    let c = Config::load(&args).unwrap();
+    assert_eq!((c.year, c.session.as_str()), (2022, "file:token.txt"));
+
+    let args = ["run", "--config", "/nonexistent/aoc.toml"].map(String::from);
+    assert!(Config::load(&args).unwrap_err().contains("can't read"));
+}
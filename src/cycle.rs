@@ -0,0 +1,55 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Walks `start, step(start), step(step(start)), ...` until a state repeats or
+/// `limit` states have been produced. Returns every state seen, in order, and
+/// `(mu, lambda)` if a repeat was found: the index of the first state on the
+/// cycle and the cycle length.
+pub fn walk<S: Hash + Eq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    limit: usize,
+) -> (Vec<S>, Option<(usize, usize)>) {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history = Vec::new();
+    let mut s = start;
+    while history.len() < limit {
+        if let Some(mu) = seen.get(&s) {
+            let lambda = history.len() - mu;
+            return (history, Some((*mu, lambda)));
+        }
+        let next = step(&s);
+        seen.insert(s.clone(), history.len());
+        history.push(s);
+        s = next;
+    }
+    history.push(s);
+    (history, None)
+}
+
+/// The state after applying `step` to `start` `n` times, skipping ahead with the
+/// detected cycle so only `mu + lambda` steps are ever simulated.
+pub fn nth_state<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (mut history, cycle) = walk(start, step, n);
+    match cycle {
+        Some((mu, lambda)) if n >= history.len() => history.swap_remove(mu + (n - mu) % lambda),
+        _ => history.swap_remove(n),
+    }
+}
+
+#[test]
+fn test_cycle() {
+    // 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 -> 4 -> ...
+    let collatz = |n: &u64| {
+        if n.is_multiple_of(2) {
+            n / 2
+        } else {
+            3 * n + 1
+        }
+    };
+    assert_eq!(walk(3, collatz, usize::MAX).1, Some((5, 3)));
+    assert_eq!(walk(1, collatz, usize::MAX).1, Some((0, 3)));
+    assert_eq!(nth_state(3, collatz, 0), 3);
+    assert_eq!(nth_state(3, collatz, 4), 8);
+    assert_eq!(nth_state(3, collatz, 1_000_000_000), 1);
+    assert_eq!(nth_state(3, collatz, 1_000_000_001), 4);
+}
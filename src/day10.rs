@@ -1,9 +1,14 @@
 use core::fmt;
 use itertools::Itertools;
-use std::{collections::HashSet, fs};
+use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point(i32, i32);
+use crate::{
+    answer::Answer,
+    geometry::{Direction, Point},
+    grid,
+    polygon::Polygon,
+    render::{Image, Rgb},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 enum Tile {
@@ -38,14 +43,15 @@ impl fmt::Display for Tile {
 
 impl Tile {
     fn from(c: char, pos: &Point) -> Self {
+        let pipe = |d1: Direction, d2: Direction| [pos.step(d1), pos.step(d2)];
         match c {
-            '|' => Self::Vertical([Point(pos.0, pos.1 - 1), Point(pos.0, pos.1 + 1)]),
-            '-' => Self::Horizontal([Point(pos.0 - 1, pos.1), Point(pos.0 + 1, pos.1)]),
-            'L' => Self::UpRight([Point(pos.0, pos.1 - 1), Point(pos.0 + 1, pos.1)]),
-            'J' => Self::UpLeft([Point(pos.0, pos.1 - 1), Point(pos.0 - 1, pos.1)]),
-            '7' => Self::DownLeft([Point(pos.0, pos.1 + 1), Point(pos.0 - 1, pos.1)]),
-            'F' => Self::DownRight([Point(pos.0, pos.1 + 1), Point(pos.0 + 1, pos.1)]),
-            'S' => Self::Start([Point(pos.0, pos.1), Point(pos.0, pos.1)]),
+            '|' => Self::Vertical(pipe(Direction::Up, Direction::Down)),
+            '-' => Self::Horizontal(pipe(Direction::Left, Direction::Right)),
+            'L' => Self::UpRight(pipe(Direction::Up, Direction::Right)),
+            'J' => Self::UpLeft(pipe(Direction::Up, Direction::Left)),
+            '7' => Self::DownLeft(pipe(Direction::Down, Direction::Left)),
+            'F' => Self::DownRight(pipe(Direction::Down, Direction::Right)),
+            'S' => Self::Start([*pos, *pos]),
             '.' => Self::Ground,
             _ => panic!("┌(° ͜ʖ͡°)┘"),
         }
@@ -74,38 +80,26 @@ impl Tile {
 
 #[derive(Debug)]
 struct Grid {
-    tiles: Vec<Vec<Tile>>,
+    tiles: grid::Grid<Tile>,
     start: Point,
 }
 
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.tiles.iter().map(|row| row.iter().join("")).join("\n")
-        )
+        write!(f, "{}", self.tiles)
     }
 }
 
 impl From<&str> for Grid {
     fn from(s: &str) -> Self {
-        let mut start = Point(0, 0);
-        let tiles = s
-            .lines()
-            .enumerate()
-            .map(|(y, l)| {
-                l.chars()
-                    .enumerate()
-                    .map(|(x, c)| Tile::from(c, &Point(x as i32, y as i32)))
-                    .inspect(|t| {
-                        if let Tile::Start(pos) = t {
-                            start = pos[0]
-                        }
-                    })
-                    .collect_vec()
-            })
-            .collect_vec();
+        let mut start = Point::default();
+        let tiles = grid::Grid::parse_indexed(s, |x, y, c| {
+            let t = Tile::from(c, &Point::new(x as i32, y as i32));
+            if let Tile::Start(pos) = t {
+                start = pos[0]
+            }
+            t
+        });
 
         let mut r = Self { tiles, start };
         r.update_starting_shape();
@@ -114,124 +108,87 @@ impl From<&str> for Grid {
 }
 
 impl Grid {
-    fn in_grid(&self, p: &Point) -> bool {
-        p.0 >= 0 && p.0 < self.tiles[0].len() as i32 && p.1 >= 0 && p.1 < self.tiles.len() as i32
-    }
-
     fn at(&self, p: &Point) -> &Tile {
-        &self.tiles[p.1 as usize][p.0 as usize]
+        &self.tiles[*p]
     }
 
     fn update_starting_shape(&mut self) {
-        let ps = [Point(-1, 0), Point(0, -1), Point(1, 0), Point(0, 1)]
-            .iter()
-            .map(|p| (Point(self.start.0 + p.0, self.start.1 + p.1), p))
-            .filter_map(|(np, p)| {
-                if self.in_grid(&np) && self.at(&np).connects_to(&self.start) {
-                    Some(p)
-                } else {
-                    None
-                }
+        let ds = Direction::ALL
+            .into_iter()
+            .filter(|d| {
+                self.tiles
+                    .get(self.start.step(*d))
+                    .is_some_and(|t| t.connects_to(&self.start))
             })
             .collect_vec();
-        let conto = [
-            Point(self.start.0 + ps[0].0, self.start.1 + ps[0].1),
-            Point(self.start.0 + ps[1].0, self.start.1 + ps[1].1),
-        ];
-        self.tiles[self.start.1 as usize][self.start.0 as usize] = match ps[..] {
-            [Point(-1, 0), Point(1, 0)] => Tile::Horizontal(conto),
-            [Point(0, -1), Point(0, 1)] => Tile::Vertical(conto),
-            [Point(-1, 0), Point(0, -1)] => Tile::UpLeft(conto),
-            [Point(0, -1), Point(1, 0)] => Tile::UpRight(conto),
-            [Point(-1, 0), Point(0, 1)] => Tile::DownLeft(conto),
-            [Point(1, 0), Point(0, 1)] => Tile::DownRight(conto),
+        let conto = [self.start.step(ds[0]), self.start.step(ds[1])];
+        self.tiles[self.start] = match ds[..] {
+            [Direction::Right, Direction::Left] => Tile::Horizontal(conto),
+            [Direction::Up, Direction::Down] => Tile::Vertical(conto),
+            [Direction::Up, Direction::Left] => Tile::UpLeft(conto),
+            [Direction::Up, Direction::Right] => Tile::UpRight(conto),
+            [Direction::Down, Direction::Left] => Tile::DownLeft(conto),
+            [Direction::Right, Direction::Down] => Tile::DownRight(conto),
             _ => panic!("(❀◦‿◦)"),
         };
     }
 
-    fn get_next(&self, p: &Point, visited: &HashSet<Point>) -> Option<Vec<Point>> {
-        let np = self
-            .at(p)
-            .get_next()
-            .unwrap()
-            .iter()
-            .filter(|p| !visited.contains(p))
-            .cloned()
-            .collect_vec();
-        if np.is_empty() {
-            None
-        } else {
-            Some(np)
-        }
-    }
-
-    fn get_loop(&self) -> HashSet<Point> {
-        let mut cur = vec![self.start];
-        let mut visited: HashSet<Point> = HashSet::from_iter(cur.iter().cloned());
-        loop {
-            cur = cur
+    /// Tiles of the loop in traversal order, starting at `start`.
+    fn get_loop(&self) -> Vec<Point> {
+        let mut path = vec![self.start];
+        let mut prev = self.start;
+        let mut cur = self.at(&self.start).get_next().unwrap()[0];
+        while cur != self.start {
+            path.push(cur);
+            let next = self
+                .at(&cur)
+                .get_next()
+                .unwrap()
                 .iter()
-                .map_while(|p| self.get_next(p, &visited))
-                .flatten()
-                .collect_vec();
-            if cur.len() < 2 {
-                break;
-            }
-            visited.extend(cur.iter());
+                .find(|p| **p != prev)
+                .copied()
+                .unwrap();
+            (prev, cur) = (cur, next);
         }
-        visited
+        path
     }
 
-    fn clean_garbage(&mut self) {
-        let lp = self.get_loop();
-        self.tiles.iter_mut().enumerate().for_each(|(y, row)| {
-            row.iter_mut().enumerate().for_each(|(x, tile)| {
-                if !lp.contains(&Point(x as i32, y as i32)) {
-                    *tile = Tile::Ground
-                }
-            })
-        });
+    /// The loop as a polygon whose vertices are the centres of its corner tiles.
+    fn get_polygon(&self) -> Polygon {
+        Polygon::new(
+            self.get_loop()
+                .into_iter()
+                .filter(|p| !matches!(self.at(p), Tile::Vertical(_) | Tile::Horizontal(_)))
+                .collect(),
+        )
     }
 
     fn get_farthest_distance(&self) -> usize {
         self.get_loop().len() / 2
     }
 
-    fn is_inside(&self, p: &Point) -> bool {
-        if Tile::Ground == *self.at(p) {
-            let mut last_corner = Tile::Ground;
-            ((p.0 + 1) as usize..self.tiles[0].len())
-                .map(|x| {
-                    match self.at(&Point(x as i32, p.1)) {
-                        Tile::Vertical(_) => 1,
-                        Tile::UpRight(c) => {
-                            last_corner = Tile::UpRight(*c);
-                            1
-                        }
-                        Tile::DownRight(c) => {
-                            last_corner = Tile::DownRight(*c);
-                            1
-                        }
-                        Tile::UpLeft(_) => match last_corner {
-                            Tile::UpRight(_) => 1,
-                            Tile::DownRight(_) => 0,
-                            _ => panic!("ᶘ ᵒᴥᵒᶅ"),
-                        },
-                        Tile::DownLeft(_) => match last_corner {
-                            Tile::UpRight(_) => 0,
-                            Tile::DownRight(_) => 1,
-                            _ => panic!("ᶘ ᵒᴥᵒᶅ"),
-                        },
-                        _ => 0,
-                    }
-                    //
-                })
-                .sum::<u32>()
-                & 1
-                != 0
-        } else {
-            false
-        }
+    /// Tiles enclosed by the loop, found by scanning rows and flipping inside/outside
+    /// on every loop tile with a northward connection.
+    fn get_enclosed(&self, lp: &HashSet<Point>) -> Vec<Point> {
+        let mut inside = false;
+        self.tiles
+            .points()
+            .filter(|p| {
+                if p.x == 0 {
+                    inside = false;
+                }
+                if !lp.contains(p) {
+                    return inside;
+                }
+                if matches!(
+                    self.at(p),
+                    Tile::Vertical(_) | Tile::UpLeft(_) | Tile::UpRight(_)
+                ) {
+                    inside = !inside;
+                }
+                false
+            })
+            .collect()
     }
 }
 
@@ -240,37 +197,36 @@ fn solution(input: &str) -> usize {
 }
 
 fn solution2(input: &str) -> usize {
-    let mut g = Grid::from(input);
-    g.clean_garbage();
-    g.tiles
-        .iter()
-        .enumerate()
-        .map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .filter(|(x, _)| g.is_inside(&Point(*x as i32, y as i32)))
-                .count()
-        })
-        .sum()
+    Grid::from(input).get_polygon().interior_points() as usize
+}
+
+fn solution2_scanline(input: &str) -> usize {
+    let g = Grid::from(input);
+    g.get_enclosed(&g.get_loop().into_iter().collect()).len()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc10s.in").unwrap();
-    assert_eq!(solution(&input), 4);
-    let input = fs::read_to_string("src/inputs/aoc10s2.in").unwrap();
-    assert_eq!(solution(&input), 8);
-    let input = fs::read_to_string("src/inputs/aoc10.in").unwrap();
-    assert_eq!(solution(&input), 7093);
-    assert_eq!(solution2(&input), 407);
+/// The pipe loop in white over the leftover pipes, with the enclosed tiles in green.
+pub fn render(input: &str) -> Image {
+    let g = Grid::from(input);
+    let lp: HashSet<Point> = g.get_loop().into_iter().collect();
+    Image::from_grid(&g.tiles, |t| match t {
+        Tile::Ground => Rgb::BLACK,
+        _ => Rgb(70, 70, 70),
+    })
+    .overlay(lp.iter().copied(), Rgb::WHITE, 1.0)
+    .overlay(g.get_enclosed(&lp), Rgb(40, 200, 80), 1.0)
+}
+
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).into()
+}
 
-    let input = fs::read_to_string("src/inputs/aoc10s3.in").unwrap();
-    assert_eq!(solution2(&input), 8);
-    let input = fs::read_to_string("src/inputs/aoc10s4.in").unwrap();
-    assert_eq!(solution2(&input), 10);
+pub fn part2(input: &str) -> Answer {
+    solution2(input).into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc10.in").unwrap();
-    (solution(&input).to_string(), solution2(&input).to_string())
+pub fn part2_scanline(input: &str) -> Answer {
+    solution2_scanline(input).into()
 }
@@ -1,7 +1,9 @@
-use std::{cmp::minmax, fs};
+use std::cmp::minmax;
 
 use itertools::Itertools;
 
+use crate::{answer::Answer, grid::Grid};
+
 #[derive(Debug)]
 struct Pos(usize, usize);
 
@@ -30,29 +32,20 @@ struct Cosmos {
 
 impl From<&str> for Cosmos {
     fn from(v: &str) -> Self {
-        let g = v
-            .lines()
-            .map(|l| l.chars().map(Tile::from).collect_vec())
-            .collect_vec();
+        let g = Grid::parse(v, Tile::from);
         let empty_rows = g
-            .iter()
+            .rows()
             .enumerate()
             .filter(|(_, row)| row.iter().all(|r| matches!(r, Tile::Empty)))
             .map(|(i, _)| i)
             .collect_vec();
-        let empty_cols = (0..g[0].len())
-            .filter(|i| (0..g.len()).all(|j| matches!(g[j][*i], Tile::Empty)))
+        let empty_cols = (0..g.width())
+            .filter(|x| g.column(*x).all(|c| matches!(c, Tile::Empty)))
             .collect_vec();
         let galaxies = g
-            .iter()
             .enumerate()
-            .flat_map(|(i, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter(|(_, t)| matches!(t, Tile::Galaxy))
-                    .map(|(j, _)| Pos(j, i))
-                    .collect_vec()
-            })
+            .filter(|(_, t)| matches!(t, Tile::Galaxy))
+            .map(|((x, y), _)| Pos(x, y))
             .collect_vec();
         Self {
             empty_rows,
@@ -89,16 +82,12 @@ fn solution(input: &str) -> (usize, usize) {
     (c.solve_for(2), c.solve_for(1000000))
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc11s.in").unwrap();
-    assert_eq!(solution(&input), (374, 82000210));
-    let input = fs::read_to_string("src/inputs/aoc11.in").unwrap();
-    assert_eq!(solution(&input), (9974721, 702770569197));
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).0.into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc11.in").unwrap();
-    let (p1, p2) = solution(&input);
-    (p1.to_string(), p2.to_string())
+pub fn part2(input: &str) -> Answer {
+    solution(input).1.into()
 }
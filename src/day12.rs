@@ -1,7 +1,12 @@
-use bitvector::BitVector;
 use itertools::Itertools;
 use rayon::{iter::ParallelBridge, iter::ParallelIterator};
-use std::fs;
+
+use crate::{
+    answer::Answer,
+    memo::{memoized, Memo},
+    parse,
+    progress::{NoProgress, Progress},
+};
 
 #[derive(Debug, Clone, Copy)]
 enum Condition {
@@ -25,59 +30,6 @@ impl From<char> for Condition {
 struct Record {
     springs: Vec<Condition>,
     checksum: Vec<u32>,
-    checksum_sum: usize,
-}
-
-fn calc_checksum_bv(v: &BitVector) -> Vec<u32> {
-    if v.is_empty() {
-        return Vec::new();
-    }
-    let mut r = Vec::new();
-    let mut prev = v.iter().next().unwrap();
-    let mut clen = 1;
-    v.iter().skip(1).for_each(|b| {
-        if b - 1 == prev {
-            clen += 1;
-        } else {
-            if clen != 0 {
-                r.push(clen);
-            }
-            clen = 1;
-        }
-        prev = b;
-    });
-    if clen != 0 {
-        r.push(clen);
-    }
-    r
-}
-
-fn early_match_checksum(v: &BitVector, checksum: &Vec<u32>) -> bool {
-    if v.is_empty() {
-        return true;
-    }
-    let mut cur_i = 0;
-    let mut prev = v.iter().next().unwrap();
-    let mut clen = 1;
-    !v.iter().skip(1).any(|b| {
-        if b - 1 == prev {
-            clen += 1;
-        } else {
-            if clen != 0 {
-                if cur_i >= checksum.len() {
-                    return true;
-                }
-                if clen != checksum[cur_i] {
-                    return true;
-                }
-                cur_i += 1;
-            }
-            clen = 1;
-        }
-        prev = b;
-        false
-    })
-    // we intentionally don't check the last checksum segment as it might be not finished
 }
 
 impl Record {
@@ -88,95 +40,87 @@ impl Record {
         for _ in 0..(ext - 1) {
             springs = [springs, springs_base.clone()].join(&Condition::Unknown);
         }
-        let checksum = checksum
-            .split(',')
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect_vec()
-            .repeat(ext as usize);
-        let checksum_sum = checksum.iter().sum::<u32>() as usize;
-        Self {
-            checksum,
-            springs,
-            checksum_sum,
-        }
-    }
-
-    fn early_exit(&self, bv: &BitVector, level: usize) -> bool {
-        let vlen = bv.len();
-        // too many bits set
-        if vlen > self.checksum_sum {
-            return true;
-        }
-        let bits_left = self.springs.len() - level;
-        // not enough bits set
-        if vlen + bits_left < self.checksum_sum {
-            return true;
-        }
-        !early_match_checksum(bv, &self.checksum)
+        let checksum = parse::ints::<u32>(checksum).unwrap().repeat(ext as usize);
+        Self { checksum, springs }
     }
 
-    fn solve_rec(&self, bv: &mut BitVector, level: usize) -> usize {
+    /// Arrangements of `springs[level..]` given that `group` checksum groups are
+    /// already closed and the current run of damaged springs is `run` long, with
+    /// `next` counting the states one spring further.
+    fn count(
+        &self,
+        mut next: impl FnMut((usize, usize, usize)) -> usize,
+        (level, group, run): (usize, usize, usize),
+    ) -> usize {
+        let expected = self.checksum.get(group).copied().unwrap_or(0) as usize;
         if level == self.springs.len() {
-            return if calc_checksum_bv(bv) == self.checksum {
-                1
-            } else {
-                0
+            return match run {
+                0 => (group == self.checksum.len()) as usize,
+                _ => (group + 1 == self.checksum.len() && run == expected) as usize,
             };
         }
-        if level > 0 && self.early_exit(bv, level) {
-            return 0;
+        let (damaged, operational) = match self.springs[level] {
+            Condition::Damaged => (true, false),
+            Condition::Operational => (false, true),
+            Condition::Unknown => (true, true),
+        };
+        let mut r = 0;
+        if damaged && run < expected {
+            r += next((level + 1, group, run + 1));
         }
-        match self.springs[level] {
-            Condition::Unknown => {
-                bv.insert(level);
-                let r = self.solve_rec(bv, level + 1);
-                bv.remove(level);
-                r + self.solve_rec(bv, level + 1)
-            }
-            Condition::Damaged => {
-                bv.insert(level);
-                let r = self.solve_rec(bv, level + 1);
-                bv.remove(level);
-                r
-            }
-            Condition::Operational => {
-                bv.remove(level);
-                self.solve_rec(bv, level + 1)
-            }
+        if operational && run == 0 {
+            r += next((level + 1, group, 0));
+        } else if operational && run == expected {
+            r += next((level + 1, group + 1, 0));
         }
+        r
     }
 
     fn solve(&self) -> usize {
-        self.solve_rec(&mut BitVector::new(self.springs.len()), 0)
+        memoized((0, 0, 0), |m: &mut Memo<_, _>, state| {
+            self.count(|s| m.get(s), state)
+        })
+    }
+
+    /// Plain backtracking over every choice, exponential in the unknown springs.
+    fn backtrack(&self, state: (usize, usize, usize)) -> usize {
+        self.count(|s| self.backtrack(s), state)
     }
 }
 
-fn solution(input: &str, ext: u32) -> usize {
+fn solution(input: &str, ext: u32, solve: fn(&Record) -> usize, progress: &impl Progress) -> usize {
+    progress.start(input.lines().count());
     input
         .lines()
         .enumerate()
         .par_bridge()
         .map(|(_, l)| Record::from(l, ext))
-        .map(|r| r.solve())
+        .map(|r| {
+            let n = solve(&r);
+            progress.advance(1);
+            n
+        })
         .sum()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc12s.in").unwrap();
-    assert_eq!(solution(&input, 1), 21);
-    assert_eq!(solution(&input, 5), 525152);
-    let input = fs::read_to_string("src/inputs/aoc12.in").unwrap();
-    assert_eq!(solution(&input, 1), 7541);
-    // this is obviously tooooooo slowwww
-    // assert_eq!(solution(&input, 5), 0);
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    part1_with_progress(input, &NoProgress)
+}
+
+pub fn part2(input: &str) -> Answer {
+    part2_with_progress(input, &NoProgress)
+}
+
+pub fn part1_with_progress(input: &str, progress: &impl Progress) -> Answer {
+    solution(input, 1, Record::solve, progress).into()
+}
+
+pub fn part2_with_progress(input: &str, progress: &impl Progress) -> Answer {
+    solution(input, 5, Record::solve, progress).into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc12.in").unwrap();
-    (
-        solution(&input, 1).to_string(),
-        //solution(&input, 5).to_string(),
-        "TBD".to_string(),
-    )
+pub fn part1_backtrack(input: &str) -> Answer {
+    solution(input, 1, |r| r.backtrack((0, 0, 0)), &NoProgress).into()
 }
@@ -1,7 +1,12 @@
-use std::fs;
-
 use itertools::Itertools;
 
+use crate::{
+    answer::Answer,
+    explain::{Entry, Explain, NoExplain},
+    grid::Grid,
+    parse,
+};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Tile {
     Ash,
@@ -20,20 +25,15 @@ impl From<char> for Tile {
 
 #[derive(Debug)]
 struct Pattern<const SMUDGE: usize> {
-    p: Vec<Vec<Tile>>,
-    p_rotated: Vec<Vec<Tile>>,
+    p: Grid<Tile>,
+    p_transposed: Grid<Tile>,
 }
 
 impl<const SMUDGE: usize> From<&str> for Pattern<SMUDGE> {
     fn from(s: &str) -> Self {
-        let p = s
-            .lines()
-            .map(|l| l.chars().map(Tile::from).collect_vec())
-            .collect_vec();
+        let p = Grid::parse(s, Tile::from);
         Self {
-            p_rotated: (0..p[0].len())
-                .map(|x| (0..p.len()).map(|y| p[y][x]).collect_vec())
-                .collect_vec(),
+            p_transposed: p.transpose(),
             p,
         }
     }
@@ -48,11 +48,12 @@ impl<const SMUDGE: usize> Pattern<SMUDGE> {
             .count()
     }
 
-    fn get_candidates(&self, vec: &[Vec<Tile>]) -> Vec<usize> {
-        vec.windows(2)
+    fn get_candidates(&self, g: &Grid<Tile>) -> Vec<usize> {
+        g.rows()
+            .tuple_windows()
             .enumerate()
-            .filter_map(|(i, r)| {
-                if self.vec_diff(&r[0], &r[1]) <= SMUDGE {
+            .filter_map(|(i, (r1, r2))| {
+                if self.vec_diff(r1, r2) <= SMUDGE {
                     Some(i)
                 } else {
                     None
@@ -66,15 +67,14 @@ impl<const SMUDGE: usize> Pattern<SMUDGE> {
     }
 
     fn get_vertical_candidates(&self) -> Vec<usize> {
-        self.get_candidates(&self.p_rotated)
+        self.get_candidates(&self.p_transposed)
     }
 
-    fn verify_mirror(&self, vec: &[Vec<Tile>], pos: usize) -> bool {
-        vec[0..=pos]
-            .iter()
+    fn verify_mirror(&self, g: &Grid<Tile>, pos: usize) -> bool {
+        (0..=pos)
             .rev()
-            .zip(vec[pos + 1..].iter())
-            .map(|(v1, v2)| self.vec_diff(v1, v2))
+            .zip(pos + 1..g.height())
+            .map(|(y1, y2)| self.vec_diff(g.row(y1), g.row(y2)))
             .sum::<usize>()
             == SMUDGE
     }
@@ -84,50 +84,88 @@ impl<const SMUDGE: usize> Pattern<SMUDGE> {
     }
 
     fn verify_vertical_mirror(&self, pos: usize) -> bool {
-        self.verify_mirror(&self.p_rotated, pos)
+        self.verify_mirror(&self.p_transposed, pos)
+    }
+
+    /// Where the first cell differs between the rows mirrored around `pos`, as
+    /// `(row, column)` of `g`.
+    fn find_smudge(&self, g: &Grid<Tile>, pos: usize) -> Option<(usize, usize)> {
+        (0..=pos)
+            .rev()
+            .zip(pos + 1..g.height())
+            .find_map(|(y1, y2)| {
+                (0..g.width())
+                    .find(|x| g.row(y1)[*x] != g.row(y2)[*x])
+                    .map(|x| (y1, x))
+            })
     }
 
-    fn find_mirror_score(&self) -> usize {
-        if let Some(x) = self
+    /// The reflection as `(horizontal, pos)`: the line lies after row (or column)
+    /// `pos`, counted from zero.
+    fn find_mirror(&self) -> (bool, usize) {
+        if let Some(y) = self
             .get_horizontal_candidates()
-            .iter()
-            .find(|p| self.verify_horizontal_mirror(**p))
+            .into_iter()
+            .find(|p| self.verify_horizontal_mirror(*p))
         {
-            (x + 1) * 100
+            (true, y)
         } else if let Some(x) = self
             .get_vertical_candidates()
-            .iter()
-            .find(|p| self.verify_vertical_mirror(**p))
+            .into_iter()
+            .find(|p| self.verify_vertical_mirror(*p))
         {
-            x + 1
+            (false, x)
         } else {
             panic!("( ಠ ಠ )");
         }
     }
+
+    fn explain(&self, n: usize, (horizontal, pos): (bool, usize)) -> Entry {
+        let (axis, lines, g) = match horizontal {
+            true => ("horizontal", "rows", &self.p),
+            false => ("vertical", "columns", &self.p_transposed),
+        };
+        let mut e = Entry::new(SMUDGE + 1, format!("pattern {n}")).fact(
+            "reflection",
+            format!("{axis}, between {lines} {} and {}", pos + 1, pos + 2),
+        );
+        if let Some((a, b)) = self.find_smudge(g, pos).filter(|_| SMUDGE > 0) {
+            let (row, col) = if horizontal { (a, b) } else { (b, a) };
+            e = e.fact("smudge", format!("row {}, column {}", row + 1, col + 1));
+        }
+        e
+    }
 }
 
-fn solution<const SMUDGE: usize>(input: &str) -> usize {
-    input
-        .replace("\r\n", "\n")
-        .split("\n\n")
-        .map(|l| Pattern::<SMUDGE>::from(l).find_mirror_score())
+fn solution<const SMUDGE: usize>(input: &str, ex: &mut impl Explain) -> usize {
+    parse::sections(input)
+        .enumerate()
+        .map(|(i, l)| {
+            let pattern = Pattern::<SMUDGE>::from(l);
+            let mirror = pattern.find_mirror();
+            ex.entry(|| pattern.explain(i + 1, mirror));
+            match mirror {
+                (true, y) => (y + 1) * 100,
+                (false, x) => x + 1,
+            }
+        })
         .sum()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc13s.in").unwrap();
-    assert_eq!(solution::<0>(&input), 405);
-    assert_eq!(solution::<1>(&input), 400);
-    let input = fs::read_to_string("src/inputs/aoc13.in").unwrap();
-    assert_eq!(solution::<0>(&input), 30575);
-    assert_eq!(solution::<1>(&input), 37478);
+/// The reflection line of every pattern, and the smudge moving it in part 2.
+pub fn explain(input: &str, ex: &mut impl Explain) -> [Answer; 2] {
+    [
+        solution::<0>(input, ex).into(),
+        solution::<1>(input, ex).into(),
+    ]
+}
+
+pub const VERSION: u32 = 3;
+
+pub fn part1(input: &str) -> Answer {
+    solution::<0>(input, &mut NoExplain).into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc13.in").unwrap();
-    (
-        solution::<0>(&input).to_string(),
-        solution::<1>(&input).to_string(),
-    )
+pub fn part2(input: &str) -> Answer {
+    solution::<1>(input, &mut NoExplain).into()
 }
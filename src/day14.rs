@@ -1,8 +1,24 @@
 use core::fmt;
-use itertools::Itertools;
-use std::fs;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+use crate::{
+    animate::{Animate, Cell, NoAnimation},
+    answer::Answer,
+    bitgrid::BitGrid,
+    cycle,
+    geometry::{Direction, Point},
+    grid,
+    render::{Image, Rgb},
+    validate::Report,
+};
+
+const SPIN: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum Tile {
     Empty,
     Cube,
@@ -22,146 +38,93 @@ impl From<char> for Tile {
 
 impl fmt::Display for Tile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Cube => '#',
-                Self::Empty => '.',
-                Self::Oval => 'O',
-            }
-        )
+        write!(f, "{}", self.symbol())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+impl Tile {
+    fn symbol(&self) -> char {
+        match self {
+            Self::Cube => '#',
+            Self::Empty => '.',
+            Self::Oval => 'O',
+        }
+    }
 
-impl Point {
-    fn from(x: i32, y: i32) -> Self {
-        Self { x, y }
+    fn color(&self) -> Rgb {
+        match self {
+            Self::Empty => Rgb(20, 20, 30),
+            Self::Cube => Rgb(110, 110, 120),
+            Self::Oval => Rgb(230, 150, 40),
+        }
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct Grid {
-    g: Vec<Vec<Tile>>,
+    ovals: BitGrid,
+    cubes: BitGrid,
 }
 
 impl From<&str> for Grid {
     fn from(s: &str) -> Self {
         Self {
-            g: s.lines()
-                .map(|l| l.chars().map(Tile::from).collect_vec())
-                .collect_vec(),
+            ovals: BitGrid::parse(s, |c| Tile::from(c) == Tile::Oval),
+            cubes: BitGrid::parse(s, |c| Tile::from(c) == Tile::Cube),
         }
     }
 }
 
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.g.iter().map(|row| row.iter().join("")).join("\n")
-        )
-    }
-}
-
-fn detect_period(v: &[usize]) -> Option<usize> {
-    if v.len() < 10 {
-        return None;
+        write!(f, "{}", self.tiles())
     }
-    (5..=v.len() / 2).find(|p| v[v.len() - p..v.len()] == v[v.len() - 2 * p..v.len() - p])
 }
 
 impl Grid {
-    fn in_grid(&self, p: &Point) -> bool {
-        p.x >= 0 && p.x < self.g[0].len() as i32 && p.y >= 0 && p.y < self.g.len() as i32
-    }
-
-    fn can_move_to(&self, p: &Point) -> bool {
-        if self.in_grid(p) {
-            match self.g[p.y as usize][p.x as usize] {
-                Tile::Empty => true,
-                Tile::Cube | Tile::Oval => false,
+    fn tiles(&self) -> grid::Grid<Tile> {
+        grid::Grid::from_fn(self.ovals.width(), self.ovals.height(), |x, y| {
+            let p = Point::new(x as i32, y as i32);
+            match (self.ovals.get(p), self.cubes.get(p)) {
+                (true, _) => Tile::Oval,
+                (_, true) => Tile::Cube,
+                _ => Tile::Empty,
             }
-        } else {
-            false
-        }
+        })
     }
 
-    fn get_tile(&self, p: &Point) -> Option<Tile> {
-        if self.in_grid(p) {
-            Some(self.g[p.y as usize][p.x as usize])
-        } else {
-            None
+    /// Moves every oval rock one cell at a time, all at once, until none can move.
+    fn tilt_towards(&mut self, dir: Direction, anim: &mut impl Animate) {
+        loop {
+            anim.frame(|| self.tiles().map(|t| Cell::new(t.symbol(), t.color())));
+            let free = !&(&self.ovals | &self.cubes);
+            let moved = &self.ovals.shift(dir) & &free;
+            if moved.is_empty() {
+                break;
+            }
+            self.ovals = &(&self.ovals ^ &moved.shift(dir.reverse())) | &moved;
         }
     }
 
-    fn roll(&mut self, p: &Point, dir: &Point) {
-        if let Some(Tile::Cube | Tile::Empty) = self.get_tile(p) {
-            return;
-        }
-        let mut cp = Point::from(p.x + dir.x, p.y + dir.y);
-        while self.can_move_to(&cp) {
-            cp.y += dir.y;
-            cp.x += dir.x;
-        }
-        cp.y -= dir.y;
-        cp.x -= dir.x;
-        self.g[p.y as usize][p.x as usize] = Tile::Empty;
-        self.g[cp.y as usize][cp.x as usize] = Tile::Oval;
+    fn spin(&self) -> Self {
+        let mut g = self.clone();
+        SPIN.iter()
+            .for_each(|dir| g.tilt_towards(*dir, &mut NoAnimation));
+        g
     }
 
-    fn tilt(&mut self, n: usize) -> usize {
-        let mut load_history = Vec::new();
-        if let Some((i, period)) = (0..n).find_map(|i| {
-            let dirs = [
-                Point::from(0, -1),
-                Point::from(-1, 0),
-                Point::from(0, 1),
-                Point::from(1, 0),
-            ];
-            let h = self.g.len();
-            let w = self.g[0].len();
-            match i % 4 {
-                0 | 1 => {
-                    (0..h).for_each(|y| {
-                        (0..w).for_each(|x| {
-                            self.roll(&Point::from(x as i32, y as i32), &dirs[i % dirs.len()])
-                        })
-                    });
-                }
-                2 | 3 => {
-                    (0..h).rev().for_each(|y| {
-                        (0..w).rev().for_each(|x| {
-                            self.roll(&Point::from(x as i32, y as i32), &dirs[i % dirs.len()])
-                        })
-                    });
-                }
-                _ => panic!("( ͡~ ͜ʖ ͡°)"),
-            };
-            load_history.push(self.get_load());
-            detect_period(&load_history).map(|p| (i, p))
-        }) {
-            let period_start = i - period - 1;
-            let period_index = (n - period_start) % period;
-            load_history[period_start + period_index - 1]
-        } else {
-            self.get_load()
-        }
+    fn tilt(self, n: usize) -> usize {
+        let mut g = cycle::nth_state(self, Self::spin, n / SPIN.len());
+        SPIN[..n % SPIN.len()]
+            .iter()
+            .for_each(|dir| g.tilt_towards(*dir, &mut NoAnimation));
+        g.get_load()
     }
 
     fn get_load(&self) -> usize {
-        self.g
-            .iter()
-            .enumerate()
-            .map(|(y, row)| {
-                row.iter().filter(|t| matches!(t, Tile::Oval)).count() * (self.g.len() - y)
-            })
+        let h = self.ovals.height();
+        (0..h)
+            .map(|y| self.ovals.row(y).count_ones() as usize * (h - y))
             .sum()
     }
 }
@@ -170,20 +133,28 @@ fn solution(input: &str, n: usize) -> usize {
     Grid::from(input).tilt(n)
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc14s.in").unwrap();
-    assert_eq!(solution(&input, 1), 136);
-    assert_eq!(solution(&input, 4000000000), 64);
-    let input = fs::read_to_string("src/inputs/aoc14.in").unwrap();
-    assert_eq!(solution(&input, 1), 110407);
-    assert_eq!(solution(&input, 4000000000), 87273);
+/// The platform after one spin cycle.
+pub fn render(input: &str) -> Image {
+    Image::from_grid(&Grid::from(input).spin().tiles(), Tile::color)
+}
+
+/// Rocks rolling through one spin cycle.
+pub fn animate(input: &str, anim: &mut impl Animate) {
+    let mut g = Grid::from(input);
+    SPIN.iter().for_each(|dir| g.tilt_towards(*dir, anim));
+}
+
+/// Checks that the platform is a rectangle of `O`, `#` and `.`.
+pub fn validate(input: &str, r: &mut Report) {
+    r.grid(input, "a rock or empty space", |c| "O#.".contains(c));
+}
+
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input, 1).into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc14.in").unwrap();
-    (
-        solution(&input, 1).to_string(),
-        solution(&input, 4000000000).to_string(),
-    )
+pub fn part2(input: &str) -> Answer {
+    solution(input, 4000000000).into()
 }
@@ -1,13 +1,13 @@
-use std::fs;
-
 use itertools::Itertools;
 
+use crate::answer::Answer;
+
 fn hash(s: &str) -> u8 {
     s.bytes().fold(0, |acc, b| ((acc + b as u32) * 17) % 256) as u8
 }
 
 fn solution(input: &str) -> u32 {
-    input.split(',').map(|s| hash(s) as u32).sum()
+    input.trim_end().split(',').map(|s| hash(s) as u32).sum()
 }
 
 #[derive(Debug)]
@@ -80,6 +80,7 @@ impl<'a> Bx<'a> {
 fn solution2(input: &str) -> usize {
     let mut boxes = (0..256).map(|_| Bx::default()).collect_vec();
     input
+        .trim_end()
         .split(',')
         .map(Step::from)
         .for_each(|step| match step.op {
@@ -101,17 +102,12 @@ fn solution2(input: &str) -> usize {
         .sum::<usize>()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc15s.in").unwrap();
-    assert_eq!(solution(&input), 1320);
-    assert_eq!(solution2(&input), 145);
-    let input = fs::read_to_string("src/inputs/aoc15.in").unwrap();
-    assert_eq!(solution(&input), 518107);
-    assert_eq!(solution2(&input), 303404);
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc15.in").unwrap();
-    (solution(&input).to_string(), solution2(&input).to_string())
+pub fn part2(input: &str) -> Answer {
+    solution2(input).into()
 }
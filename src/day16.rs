@@ -1,8 +1,15 @@
-use itertools::Itertools;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
+use std::collections::VecDeque;
+
+use crate::{
+    animate::{Animate, Cell, NoAnimation},
+    answer::Answer,
+    bitgrid::BitGrid,
+    geometry::{Direction, Point},
+    grid,
+    progress::{self, Progress},
+    render::{Image, Rgb},
+    validate::Report,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -37,67 +44,34 @@ impl From<char> for Tile {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-impl Direction {
-    fn apply_mirror(&self, mt: MirrorType) -> Self {
+impl Tile {
+    fn symbol(&self) -> char {
         match self {
-            Self::Down => match mt {
-                MirrorType::Left => Self::Right,
-                MirrorType::Right => Self::Left,
-            },
-            Self::Up => match mt {
-                MirrorType::Left => Self::Left,
-                MirrorType::Right => Self::Right,
-            },
-            Self::Left => match mt {
-                MirrorType::Left => Self::Up,
-                MirrorType::Right => Self::Down,
-            },
-            Self::Right => match mt {
-                MirrorType::Left => Self::Down,
-                MirrorType::Right => Self::Up,
-            },
+            Self::Empty => '.',
+            Self::Mirror(MirrorType::Right) => '/',
+            Self::Mirror(MirrorType::Left) => '\\',
+            Self::Splitter(SplitterType::Vertical) => '|',
+            Self::Splitter(SplitterType::Horizontal) => '-',
         }
     }
+}
 
-    fn apply_splitter(&self, st: SplitterType) -> Option<[Self; 2]> {
-        match self {
-            Self::Down | Self::Up => match st {
-                SplitterType::Horizontal => Some([Self::Left, Self::Right]),
-                SplitterType::Vertical => None,
-            },
-            Self::Right | Self::Left => match st {
-                SplitterType::Horizontal => None,
-                SplitterType::Vertical => Some([Self::Up, Self::Down]),
-            },
+impl MirrorType {
+    fn reflect(&self, dir: Direction) -> Direction {
+        match (self, dir.is_horizontal()) {
+            (Self::Right, true) | (Self::Left, false) => dir.turn_left(),
+            (Self::Right, false) | (Self::Left, true) => dir.turn_right(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn from(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    fn next(&self, dir: &Direction) -> Self {
-        match dir {
-            Direction::Down => Self::from(self.x, self.y + 1),
-            Direction::Up => Self::from(self.x, self.y - 1),
-            Direction::Left => Self::from(self.x - 1, self.y),
-            Direction::Right => Self::from(self.x + 1, self.y),
+impl SplitterType {
+    fn split(&self, dir: Direction) -> Option<[Direction; 2]> {
+        match (self, dir.is_horizontal()) {
+            (Self::Vertical, true) | (Self::Horizontal, false) => {
+                Some([dir.turn_left(), dir.turn_right()])
+            }
+            (Self::Vertical, false) | (Self::Horizontal, true) => None,
         }
     }
 }
@@ -115,84 +89,115 @@ impl Beam {
 }
 
 struct Grid {
-    g: Vec<Vec<Tile>>,
+    g: grid::Grid<Tile>,
 }
 
 impl From<&str> for Grid {
     fn from(s: &str) -> Self {
         Self {
-            g: s.lines()
-                .map(|l| l.chars().map(Tile::from).collect_vec())
-                .collect_vec(),
+            g: grid::Grid::parse(s, Tile::from),
         }
     }
 }
 
 impl Grid {
-    fn in_grid(&self, p: &Point) -> bool {
-        p.x >= 0 && p.x < self.g[0].len() as i32 && p.y >= 0 && p.y < self.g.len() as i32
-    }
-
-    fn get_tile(&self, p: &Point) -> Tile {
-        self.g[p.y as usize][p.x as usize]
+    fn trace(&self, b: Beam) -> usize {
+        let (w, h) = (self.g.width(), self.g.height());
+        self.energize(b, &mut NoAnimation)
+            .iter()
+            .fold(BitGrid::new(w, h), |a, e| &a | e)
+            .count_ones()
     }
 
-    fn trace(&self, b: Beam) -> usize {
-        let mut energized: Vec<Vec<HashSet<Direction>>> =
-            vec![vec![HashSet::new(); self.g[0].len()]; self.g.len()];
+    /// Cells crossed by the beam, one layer per beam direction.
+    fn energize(&self, b: Beam, anim: &mut impl Animate) -> [BitGrid; 4] {
+        let (w, h) = (self.g.width(), self.g.height());
+        // one layer per beam direction, indexed by `Direction as usize`
+        let mut energized = Direction::ALL.map(|_| BitGrid::new(w, h));
         let mut q: VecDeque<Beam> = VecDeque::new();
         q.push_back(b);
-        while !q.is_empty() {
-            let b = q.pop_front().unwrap();
-            if !self.in_grid(&b.p) {
-                continue;
+        // beams left in the current generation, i.e. at the same distance from the start
+        let mut generation = 0;
+        while let Some(b) = q.pop_front() {
+            if generation == 0 {
+                anim.frame(|| self.frame(&energized, q.iter().chain([&b])));
+                generation = q.len() + 1;
             }
-            if energized[b.p.y as usize][b.p.x as usize].contains(&b.dir) {
+            generation -= 1;
+            let Some(tile) = self.g.get(b.p) else {
+                continue;
+            };
+            if energized[b.dir as usize].set(b.p, true) {
                 continue;
             }
-            energized[b.p.y as usize][b.p.x as usize].insert(b.dir);
-            match self.get_tile(&b.p) {
-                Tile::Empty => q.push_back(Beam::from(&b.p.next(&b.dir), b.dir)),
+            match *tile {
+                Tile::Empty => q.push_back(Beam::from(&b.p.step(b.dir), b.dir)),
                 Tile::Mirror(mt) => {
-                    let new_dir = b.dir.apply_mirror(mt);
-                    q.push_back(Beam::from(&b.p.next(&new_dir), new_dir))
+                    let new_dir = mt.reflect(b.dir);
+                    q.push_back(Beam::from(&b.p.step(new_dir), new_dir))
                 }
                 Tile::Splitter(st) => {
-                    if let Some(new_dir) = b.dir.apply_splitter(st) {
+                    if let Some(new_dir) = st.split(b.dir) {
                         new_dir
                             .iter()
-                            .for_each(|dir| q.push_back(Beam::from(&b.p.next(dir), *dir)))
+                            .for_each(|dir| q.push_back(Beam::from(&b.p.step(*dir), *dir)))
                     } else {
-                        q.push_back(Beam::from(&b.p.next(&b.dir), b.dir))
+                        q.push_back(Beam::from(&b.p.step(b.dir), b.dir))
                     }
                 }
             }
         }
         energized
-            .iter()
-            .map(|row| row.iter().filter(|t| !t.is_empty()).count())
-            .sum()
+    }
+
+    /// Contraptions with energized cells lit up and the beam heads in red.
+    fn frame<'a>(
+        &self,
+        energized: &[BitGrid; 4],
+        beams: impl Iterator<Item = &'a Beam>,
+    ) -> grid::Grid<Cell> {
+        let mut r = grid::Grid::from_fn(self.g.width(), self.g.height(), |x, y| {
+            let p = Point::new(x as i32, y as i32);
+            let color = if energized.iter().any(|e| e.get(p)) {
+                Rgb(250, 200, 40)
+            } else {
+                Rgb(90, 90, 90)
+            };
+            Cell::new(self.g[p].symbol(), color)
+        });
+        beams.for_each(|b| {
+            if let Some(c) = r.get_mut(b.p) {
+                *c = Cell::new('*', Rgb(255, 40, 40));
+            }
+        });
+        r
     }
 }
 
 fn solution(input: &str) -> usize {
-    Grid::from(input).trace(Beam::from(&Point::from(0, 0), Direction::Right))
+    Grid::from(input).trace(Beam::from(&Point::new(0, 0), Direction::Right))
 }
 
-fn solution2(input: &str) -> usize {
+fn solution2(input: &str, progress: &impl Progress) -> usize {
     let g = Grid::from(input);
-    let w = g.g[0].len();
-    let h = g.g.len();
+    let w = g.g.width();
+    let h = g.g.height();
+    progress.start(2 * (w + h));
+    let trace = |b: Beam| {
+        let n = g.trace(b);
+        progress.advance(1);
+        n
+    };
     let top_max = (0..w)
         .into_par_iter()
-        .map(|x| g.trace(Beam::from(&Point::from(x as i32, 0), Direction::Down)))
+        .map(|x| trace(Beam::from(&Point::new(x as i32, 0), Direction::Down)))
         .max()
         .unwrap();
     let bottom_max = (0..w)
         .into_par_iter()
         .map(|x| {
-            g.trace(Beam::from(
-                &Point::from(x as i32, h as i32 - 1),
+            trace(Beam::from(
+                &Point::new(x as i32, h as i32 - 1),
                 Direction::Up,
             ))
         })
@@ -200,14 +205,14 @@ fn solution2(input: &str) -> usize {
         .unwrap();
     let left_max = (0..h)
         .into_par_iter()
-        .map(|y| g.trace(Beam::from(&Point::from(0, y as i32), Direction::Right)))
+        .map(|y| trace(Beam::from(&Point::new(0, y as i32), Direction::Right)))
         .max()
         .unwrap();
     let right_max = (0..h)
         .into_par_iter()
         .map(|y| {
-            g.trace(Beam::from(
-                &Point::from(w as i32 - 1, y as i32),
+            trace(Beam::from(
+                &Point::new(w as i32 - 1, y as i32),
                 Direction::Left,
             ))
         })
@@ -219,17 +224,50 @@ fn solution2(input: &str) -> usize {
         .unwrap()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc16s.in").unwrap();
-    assert_eq!(solution(&input), 46);
-    assert_eq!(solution2(&input), 51);
-    let input = fs::read_to_string("src/inputs/aoc16.in").unwrap();
-    assert_eq!(solution(&input), 7939);
-    assert_eq!(solution2(&input), 8318);
+/// Heatmap of how many beam directions cross each cell for the part 1 beam,
+/// with the contraptions drawn faintly on top.
+pub fn render(input: &str) -> Image {
+    let g = Grid::from(input);
+    let layers = g.energize(
+        Beam::from(&Point::new(0, 0), Direction::Right),
+        &mut NoAnimation,
+    );
+    let heat = grid::Grid::from_fn(g.g.width(), g.g.height(), |x, y| {
+        let p = Point::new(x as i32, y as i32);
+        layers.iter().filter(|l| l.get(p)).count()
+    });
+    let contraptions = g.g.points().filter(|p| g.g[*p] != Tile::Empty);
+    Image::from_grid(&heat, |n| Rgb::heat(*n as f64 / 4.0)).overlay(
+        contraptions.collect::<Vec<_>>(),
+        Rgb(80, 140, 255),
+        0.5,
+    )
+}
+
+/// The part 1 beam spreading through the contraption.
+pub fn animate(input: &str, anim: &mut impl Animate) {
+    let g = Grid::from(input);
+    let energized = g.energize(Beam::from(&Point::new(0, 0), Direction::Right), anim);
+    anim.frame(|| g.frame(&energized, [].iter()));
+}
+
+/// Checks that the contraption is a rectangle of `.`, mirrors and splitters.
+pub fn validate(input: &str, r: &mut Report) {
+    r.grid(input, "a mirror, splitter or empty space", |c| {
+        r".|-/\".contains(c)
+    });
+}
+
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).into()
+}
+
+pub fn part2(input: &str) -> Answer {
+    part2_with_progress(input, &progress::NoProgress)
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc16.in").unwrap();
-    (solution(&input).to_string(), solution2(&input).to_string())
+pub fn part2_with_progress(input: &str, progress: &impl Progress) -> Answer {
+    solution2(input, progress).into()
 }
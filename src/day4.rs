@@ -1,77 +1,95 @@
-use std::{collections::HashSet, fs};
-
-use itertools::Itertools;
-
-#[derive(Debug)]
-struct Card {
-    winning: HashSet<u32>,
-    you_have: HashSet<u32>,
-}
-
-fn get_numbers(s: &str) -> HashSet<u32> {
-    s.split(' ')
-        .filter(|n| !n.is_empty())
-        .map(|n| n.parse::<u32>().unwrap())
-        .collect()
-}
-
-impl From<&str> for Card {
-    fn from(s: &str) -> Self {
-        let (winning, you_have) = s.split_once(':').unwrap().1.split_once('|').unwrap();
-        Self {
-            winning: get_numbers(winning),
-            you_have: get_numbers(you_have),
-        }
-    }
-}
-
-impl Card {
-    fn get_matches(&self) -> usize {
-        self.you_have
-            .iter()
-            .filter(|yh| self.winning.contains(*yh))
-            .count()
-    }
-
-    fn get_points(&self) -> u32 {
-        let p = self.get_matches();
-        if p != 0 {
-            2u32.pow(p as u32 - 1)
-        } else {
-            0
-        }
-    }
-}
-
-fn p1(cards: &[Card]) -> u32 {
-    cards.iter().map(|c| c.get_points()).sum()
-}
-
-fn p2(cards: &[Card]) -> u32 {
-    let mut v = vec![1; cards.len()];
-    cards.iter().enumerate().for_each(|(i, c)| {
-        (0..c.get_matches()).for_each(|j| {
-            v[i + j + 1] += v[i];
-        });
-    });
-    v.iter().sum()
-}
-
-fn solution(input: &str) -> (u32, u32) {
-    let cards = input.lines().map(Card::from).collect_vec();
-    (p1(&cards), p2(&cards))
-}
-
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc4s.in").unwrap();
-    assert_eq!(solution(&input), (13, 30));
-    let input = fs::read_to_string("src/inputs/aoc4.in").unwrap();
-    assert_eq!(solution(&input), (33950, 14814534));
-}
-
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc4.in").unwrap();
-    let (p1, p2) = solution(&input);
-    (p1.to_string(), p2.to_string())
-}
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{
+    answer::Answer,
+    explain::{Entry, Explain, NoExplain},
+    math, parse,
+};
+
+#[derive(Debug)]
+struct Card {
+    winning: HashSet<u32>,
+    you_have: HashSet<u32>,
+}
+
+fn get_numbers(s: &str) -> HashSet<u32> {
+    parse::numbers(s).unwrap().into_iter().collect()
+}
+
+impl From<&str> for Card {
+    fn from(s: &str) -> Self {
+        let (_, numbers) = parse::split_pair(s, ":").unwrap();
+        let (winning, you_have) = parse::split_pair(numbers, "|").unwrap();
+        Self {
+            winning: get_numbers(winning),
+            you_have: get_numbers(you_have),
+        }
+    }
+}
+
+impl Card {
+    fn get_matches(&self) -> usize {
+        self.you_have
+            .iter()
+            .filter(|yh| self.winning.contains(*yh))
+            .count()
+    }
+
+    fn get_points(&self) -> u64 {
+        match self.get_matches() {
+            0 => 0,
+            p => math::shl(1u64, p as u32 - 1, "day4 p1: points"),
+        }
+    }
+}
+
+fn p1(cards: &[Card], ex: &mut impl Explain) -> u64 {
+    cards.iter().enumerate().for_each(|(i, c)| {
+        ex.entry(|| {
+            Entry::new(1, format!("card {}", i + 1))
+                .fact("matches", c.get_matches())
+                .fact("points", c.get_points())
+        })
+    });
+    math::sum(cards.iter().map(|c| c.get_points()), "day4 p1: points")
+}
+
+fn p2(cards: &[Card], ex: &mut impl Explain) -> u64 {
+    let mut v = vec![1u64; cards.len()];
+    cards.iter().enumerate().for_each(|(i, c)| {
+        // copies are only won of cards that exist, however many numbers match
+        let won = (i + 1..=i + c.get_matches()).take_while(|j| *j < cards.len());
+        ex.entry(|| {
+            let won = won.clone().map(|j| format!("card {}", j + 1)).collect_vec();
+            Entry::new(2, format!("card {}", i + 1))
+                .fact("copies", v[i])
+                .fact("matches", c.get_matches())
+                .fact("each copy wins", won)
+        });
+        won.for_each(|j| v[j] = math::add(v[j], v[i], "day4 p2: card copies"));
+    });
+    math::sum(v, "day4 p2: card copies")
+}
+
+fn solution(input: &str, ex: &mut impl Explain) -> (u64, u64) {
+    let cards = input.lines().map(Card::from).collect_vec();
+    (p1(&cards, ex), p2(&cards, ex))
+}
+
+/// Each card's points, then the cascade of card copies.
+pub fn explain(input: &str, ex: &mut impl Explain) -> [Answer; 2] {
+    let (p1, p2) = solution(input, ex);
+    [p1.into(), p2.into()]
+}
+
+pub const VERSION: u32 = 3;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input, &mut NoExplain).0.into()
+}
+
+pub fn part2(input: &str) -> Answer {
+    solution(input, &mut NoExplain).1.into()
+}
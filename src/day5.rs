@@ -1,99 +1,89 @@
-use std::{collections::BTreeMap, fs, str::Lines};
+use std::collections::BTreeMap;
 
 use itertools::Itertools;
-use rayon::prelude::*;
+
+use crate::{
+    answer::Answer,
+    interval::{Interval, IntervalSet},
+    parse,
+};
 
 #[derive(Debug)]
 struct RangeMap {
-    m: BTreeMap<u64, (u64, u64)>,
+    m: BTreeMap<i64, (i64, i64)>,
 }
 
-impl From<&mut &mut Lines<'_>> for RangeMap {
-    fn from(v: &mut &mut Lines) -> Self {
+impl From<&str> for RangeMap {
+    fn from(section: &str) -> Self {
         Self {
-            m: v.map_while(|l| {
-                if l.is_empty() {
-                    None
-                } else {
-                    let d = l
-                        .split(' ')
-                        .map(|n| n.parse::<u64>().unwrap())
-                        .collect_vec();
-                    Some((d[1], (d[0], d[2])))
-                }
-            })
-            .collect(),
+            m: section
+                .lines()
+                .skip(1) // "x-to-y map:" header
+                .map(|l| {
+                    let d = parse::numbers::<i64>(l).unwrap();
+                    (d[1], (d[0], d[2]))
+                })
+                .collect(),
         }
     }
 }
 
 impl RangeMap {
-    fn at(&self, v: u64) -> u64 {
-        let ub = self.m.upper_bound(std::ops::Bound::Included(&v));
-        if let Some((ub_k, ub_v)) = ub.key_value() {
-            if (*ub_k..(*ub_k + ub_v.1)).contains(&v) {
+    fn at(&self, v: i64) -> i64 {
+        if let Some((ub_k, ub_v)) = self.m.range(..=v).next_back() {
+            if Interval::from_len(*ub_k, ub_v.1).contains(v) {
                 return ub_v.0 + v - *ub_k;
             }
         }
         v
     }
-}
 
-fn read_seeds(lit: &mut Lines) -> Vec<u64> {
-    lit.next()
-        .unwrap()
-        .split_once(':')
-        .unwrap()
-        .1
-        .split(' ')
-        .filter(|d| !d.is_empty())
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect_vec()
-}
-
-fn read_maps(mut lit: &mut Lines) -> Vec<RangeMap> {
-    let mut r = Vec::new();
-    while lit.next().is_some() {
-        r.push(RangeMap::from(&mut lit));
+    /// Maps every value in `s` at once, splitting its intervals at range boundaries.
+    fn map_ranges(&self, s: &IntervalSet) -> IntervalSet {
+        let sources: IntervalSet = self
+            .m
+            .iter()
+            .map(|(src, (_, len))| Interval::from_len(*src, *len))
+            .collect();
+        self.m
+            .iter()
+            .map(|(src, (dst, len))| {
+                s.intersection(&Interval::from_len(*src, *len).into())
+                    .shift(dst - src)
+            })
+            .fold(s.difference(&sources), |acc, m| acc.union(&m))
     }
-    r
 }
 
-fn solution(input: &str) -> (u64, u64) {
-    let mut lit = input.lines();
-    let seeds = read_seeds(&mut lit);
-    lit.next(); // empty line
-    let tr = read_maps(&mut lit);
+fn solution(input: &str) -> (i64, i64) {
+    let mut sections = parse::sections(input);
+    let (_, seeds) = parse::labeled_numbers::<i64>(sections.next().unwrap()).unwrap();
+    let tr = sections.map(RangeMap::from).collect_vec();
     (
         seeds
             .iter()
             .map(|s| tr.iter().fold(*s, |acc, v| v.at(acc)))
             .min()
             .unwrap(),
-        seeds
-            .chunks(2)
-            .map(|chunk| {
-                (chunk[0]..(chunk[0] + chunk[1]))
-                    .into_par_iter()
-                    .map(|s| tr.iter().fold(s, |acc, v| v.at(acc)))
-                    .min()
-                    .unwrap()
-            })
+        tr.iter()
+            .fold(
+                seeds
+                    .chunks(2)
+                    .map(|chunk| Interval::from_len(chunk[0], chunk[1]))
+                    .collect(),
+                |acc, v| v.map_ranges(&acc),
+            )
             .min()
             .unwrap(),
     )
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc5s.in").unwrap();
-    assert_eq!(solution(&input), (35, 46));
-    let input = fs::read_to_string("src/inputs/aoc5.in").unwrap();
-    assert_eq!(solution(&input), (535088217, 51399228));
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).0.into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc5.in").unwrap();
-    let (p1, p2) = solution(&input);
-    (p1.to_string(), p2.to_string())
+pub fn part2(input: &str) -> Answer {
+    solution(input).1.into()
 }
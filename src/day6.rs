@@ -1,4 +1,6 @@
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{answer::Answer, math, parse};
 
 struct Race {
     time: u64,
@@ -10,53 +12,71 @@ impl Race {
         Self { time, distance }
     }
 
-    fn hold(&self, seconds: u64) -> u64 {
-        (self.time - seconds) * seconds
+    /// Hold times `h` beating the record satisfy `(time - h) * h > distance`,
+    /// i.e. `h² - time * h + distance < 0`.
+    fn get_win_number(&self) -> usize {
+        math::quadratic_below_zero(1, -(self.time as i128), self.distance as i128)
+            .map_or(0, |(lo, hi)| (hi - lo + 1) as usize)
     }
 
-    fn get_win_number(&self) -> usize {
-        (1..self.time)
-            .into_par_iter()
-            .filter(|t| self.hold(*t) > self.distance)
+    /// Tries every hold time.
+    fn get_win_number_brute(&self) -> usize {
+        (0..=self.time)
+            .filter(|h| (self.time - h) * h > self.distance)
             .count()
     }
 }
 
-fn solution(input: &[Race]) -> usize {
-    input
-        .par_iter()
-        .map(|race| race.get_win_number())
-        .product::<usize>()
+/// Parses the `Time:`/`Distance:` table; with `kerning` the columns are one race
+/// whose numbers are written with spaces in between.
+fn parse_races(input: &str, kerning: bool) -> Vec<Race> {
+    let rows = input
+        .lines()
+        .map(|l| {
+            let (_, values) = parse::split_pair(l, ":").unwrap();
+            if kerning {
+                parse::numbers(&values.replace(' ', "")).unwrap()
+            } else {
+                parse::numbers(values).unwrap()
+            }
+        })
+        .collect::<Vec<Vec<u64>>>();
+    rows[0]
+        .iter()
+        .zip(&rows[1])
+        .map(|(t, d)| Race::from(*t, *d))
+        .collect()
+}
+
+fn solution(input: &[Race], win_number: fn(&Race) -> usize) -> usize {
+    input.par_iter().map(win_number).product::<usize>()
 }
 
 #[test]
-fn test_run() {
-    assert_eq!(
-        solution(&[Race::from(7, 9), Race::from(15, 40), Race::from(30, 200)]),
-        288
-    );
-    assert_eq!(solution(&[Race::from(71530, 940200)]), 71503);
+fn test_races() {
+    let races = [Race::from(7, 9), Race::from(15, 40), Race::from(30, 200)];
+    assert_eq!(solution(&races, Race::get_win_number), 288);
+    assert_eq!(solution(&races, Race::get_win_number_brute), 288);
     assert_eq!(
-        solution(&[
-            Race::from(48, 296),
-            Race::from(93, 1928),
-            Race::from(85, 1236),
-            Race::from(95, 1391),
-        ]),
-        2756160
+        solution(&[Race::from(71530, 940200)], Race::get_win_number),
+        71503
     );
-    assert_eq!(solution(&[Race::from(48938595, 296192812361391)]), 34788142);
-}
-
-pub fn run() -> (String, String) {
-    (
-        solution(&[
-            Race::from(48, 296),
-            Race::from(93, 1928),
-            Race::from(85, 1236),
-            Race::from(95, 1391),
-        ])
-        .to_string(),
-        solution(&[Race::from(48938595, 296192812361391)]).to_string(),
-    )
+}
+
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    solution(&parse_races(input, false), Race::get_win_number).into()
+}
+
+pub fn part2(input: &str) -> Answer {
+    solution(&parse_races(input, true), Race::get_win_number).into()
+}
+
+pub fn part1_brute(input: &str) -> Answer {
+    solution(&parse_races(input, false), Race::get_win_number_brute).into()
+}
+
+pub fn part2_brute(input: &str) -> Answer {
+    solution(&parse_races(input, true), Race::get_win_number_brute).into()
 }
@@ -1,9 +1,15 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fs;
 
 use itertools::Itertools;
 
+use crate::{
+    answer::Answer,
+    explain::{Entry, Explain, NoExplain},
+    math,
+    validate::Report,
+};
+
 #[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 struct Card {
     c: u8,
@@ -31,8 +37,30 @@ impl Card {
             joker,
         }
     }
+
+    fn symbol(&self) -> char {
+        match self.c {
+            0 | 11 => 'J',
+            10 => 'T',
+            12 => 'Q',
+            13 => 'K',
+            14 => 'A',
+            c => (b'0' + c) as char,
+        }
+    }
 }
 
+/// Hand categories by score.
+const CATEGORIES: [&str; 7] = [
+    "high card",
+    "one pair",
+    "two pair",
+    "three of a kind",
+    "full house",
+    "four of a kind",
+    "five of a kind",
+];
+
 #[derive(Debug)]
 struct Hand {
     cards: [Card; 5],
@@ -143,7 +171,7 @@ impl Eq for Hand {}
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct Round {
     hand: Hand,
-    bid: u32,
+    bid: u64,
 }
 
 impl Round {
@@ -151,7 +179,7 @@ impl Round {
         let (hand, bid) = s.split_once(' ').unwrap();
         Self {
             hand: Hand::from(hand, joker),
-            bid: bid.parse::<u32>().unwrap(),
+            bid: bid.parse::<u64>().unwrap(),
         }
     }
 }
@@ -173,29 +201,79 @@ impl Game {
     }
 }
 
-fn solution(input: &str, joker: bool) -> usize {
-    Game::from(input, joker)
-        .rounds
-        .iter()
-        .enumerate()
-        .map(|(i, r)| (i + 1) * r.bid as usize)
-        .sum()
+fn solution(input: &str, joker: bool, ex: &mut impl Explain) -> u64 {
+    let game = Game::from(input, joker);
+    let part = joker as usize + 1;
+    let (rank_bid, total) = (
+        format!("day7 p{part}: rank * bid"),
+        format!("day7 p{part}: total winnings"),
+    );
+    let winnings = game.rounds.iter().enumerate().map(|(i, r)| {
+        ex.entry(|| {
+            Entry::new(part, r.hand.cards.iter().map(Card::symbol).join(""))
+                .fact("category", CATEGORIES[r.hand.score() as usize])
+                .fact("rank", i + 1)
+                .fact("bid", r.bid)
+        });
+        math::mul(i as u64 + 1, r.bid, &rank_bid)
+    });
+    math::sum(winnings, &total)
+}
+
+/// Every hand's category and rank, weakest first, without and with jokers.
+pub fn explain(input: &str, ex: &mut impl Explain) -> [Answer; 2] {
+    [false, true].map(|joker| solution(input, joker, ex).into())
+}
+
+/// Checks that every line is a hand of exactly five cards and a numeric bid.
+pub fn validate(input: &str, r: &mut Report) {
+    let mut hands = 0;
+    for (i, line) in input.lines().enumerate() {
+        let Some((hand, bid)) = line.split_once(' ') else {
+            r.problem(i, 0, "expected `HAND BID`");
+            continue;
+        };
+        hands += 1;
+        let n = hand.chars().count();
+        if n != 5 {
+            r.problem(i, 0, format!("hand has {n} cards, expected 5"));
+        }
+        hand.chars()
+            .enumerate()
+            .filter(|(_, c)| !"23456789TJQKA".contains(*c))
+            .for_each(|(j, c)| r.problem(i, j, format!("{c:?} is not a card")));
+        if bid.parse::<u64>().is_err() {
+            r.problem(i, n + 1, format!("bid {bid:?} is not a number"));
+        }
+    }
+    r.stat("hands", hands);
+}
+
+pub const VERSION: u32 = 4;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input, false, &mut NoExplain).into()
+}
+
+pub fn part2(input: &str) -> Answer {
+    solution(input, true, &mut NoExplain).into()
 }
 
 #[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc7s.in").unwrap();
-    assert_eq!(solution(&input, false), 6440);
-    assert_eq!(solution(&input, true), 5905);
-    let input = fs::read_to_string("src/inputs/aoc7.in").unwrap();
-    assert_eq!(solution(&input, false), 247961593);
-    assert_eq!(solution(&input, true), 248750699);
-}
-
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc7.in").unwrap();
-    (
-        solution(&input, false).to_string(),
-        solution(&input, true).to_string(),
-    )
+fn test_validate() {
+    let mut r = Report::default();
+    validate("K99QT 53\nK9Q1 x\nAAAAA\nKKK€Q 7b\n", &mut r);
+    let found = r.problems.iter().map(|p| (p.line, p.col, p.msg.as_str()));
+    assert_eq!(
+        found.collect_vec(),
+        [
+            (2, 1, "hand has 4 cards, expected 5"),
+            (2, 4, "'1' is not a card"),
+            (2, 6, "bid \"x\" is not a number"),
+            (3, 1, "expected `HAND BID`"),
+            (4, 4, "'€' is not a card"),
+            (4, 7, "bid \"7b\" is not a number"),
+        ]
+    );
+    assert_eq!(r.stats, [("hands", "3".to_string())]);
 }
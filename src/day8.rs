@@ -1,73 +1,180 @@
-use itertools::Itertools;
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
 
-fn p1(turns: &str, map: &HashMap<&str, (&str, &str)>) -> usize {
-    if !map.contains_key("AAA") {
-        return 0;
+use itertools::{EitherOrBoth, Itertools};
+
+use crate::{
+    answer::Answer,
+    graph::{self, Graph, NodeId, Trajectory},
+    math, parse,
+    validate::Report,
+};
+
+struct Network<'a> {
+    turns: &'a str,
+    graph: Graph<char>,
+}
+
+impl<'a> From<&'a str> for Network<'a> {
+    fn from(input: &'a str) -> Self {
+        let mut sections = parse::sections(input);
+        let turns = sections.next().unwrap();
+        let mut graph = Graph::new();
+        sections.next().unwrap().lines().for_each(|l| {
+            let (node, (left, right)) = parse::record(l).unwrap();
+            // successors are stored in `turns` order: left first, then right
+            graph.add_edge(node, left, 'L');
+            graph.add_edge(node, right, 'R');
+        });
+        Self { turns, graph }
     }
-    let mut cur_node = "AAA";
-    let mut cur_turn = 0;
-    while cur_node != "ZZZ" {
-        let t = turns.chars().nth(cur_turn % turns.len()).unwrap();
-        cur_node = match t {
-            'L' => map[cur_node].0,
-            'R' => map[cur_node].1,
+}
+
+impl Network<'_> {
+    /// Follows the turn at index `i` of the instructions from `node`.
+    fn step(&self, (node, i): &(NodeId, usize)) -> (NodeId, usize) {
+        let next = match self.turns.as_bytes()[*i] {
+            b'L' => self.graph.successors(*node)[0].0,
+            b'R' => self.graph.successors(*node)[1].0,
             _ => panic!(r"¯\(◉◡◔)/¯"),
         };
-        cur_turn += 1;
+        (next, (i + 1) % self.turns.len())
+    }
+
+    fn p1(&self) -> usize {
+        let (Some(start), Some(end)) = (self.graph.id("AAA"), self.graph.id("ZZZ")) else {
+            return 0;
+        };
+        let mut state = (start, 0);
+        let mut n = 0;
+        while state.0 != end {
+            state = self.step(&state);
+            n += 1;
+        }
+        n
+    }
+
+    /// How every ghost moves through `(node, instruction index)` states.
+    fn trajectories(&self) -> Vec<Trajectory> {
+        self.graph
+            .nodes()
+            .filter(|n| self.graph.name(*n).ends_with('A'))
+            .map(|n| {
+                Trajectory::new(
+                    (n, 0),
+                    |s| self.step(s),
+                    |(n, _)| self.graph.name(*n).ends_with('Z'),
+                )
+            })
+            .collect_vec()
+    }
+
+    fn p2(&self) -> usize {
+        let trajectories = self.trajectories();
+        if trajectories.iter().all(Trajectory::is_simple) {
+            return math::lcm_all(trajectories.iter().map(|tr| tr.cycle));
+        }
+        eprintln!("warning: day8: ghost cycles don't line up with their Z hits, LCM of first hits doesn't apply; using CRT");
+        graph::first_common_hit(&trajectories).expect("ghosts never meet on Z nodes")
     }
-    cur_turn
 }
 
-fn p2(turns: &str, map: &HashMap<&str, (&str, &str)>) -> usize {
-    let mut cur_nodes = map
-        .keys()
-        .filter(|k| k.ends_with('A'))
-        .cloned()
-        .collect_vec();
-    let mut cur_turn = 0;
-    let mut zs = Vec::new();
-    while zs.len() != cur_nodes.len() {
-        if cur_nodes.iter().any(|n| n.ends_with('Z')) {
-            zs.push(cur_turn);
+/// The network in Graphviz DOT format.
+pub fn dot(input: &str) -> String {
+    Network::from(input).graph.to_dot("day8")
+}
+
+/// Checks the `L`/`R` instructions and that every node line reads
+/// `AAA = (BBB, CCC)`, with every referenced node defined exactly once.
+pub fn validate(input: &str, r: &mut Report) {
+    let mut lines = input.lines().enumerate();
+    let turns = lines.next().map_or("", |(_, l)| l);
+    if turns.is_empty() {
+        r.problem(0, 0, "no instructions");
+    }
+    turns
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| !"LR".contains(*c))
+        .for_each(|(j, c)| r.problem(0, j, format!("{c:?} is not a turn, expected L or R")));
+    match lines.next() {
+        Some((_, "")) => {}
+        Some((i, _)) => r.problem(i, 0, "expected a blank line after the instructions"),
+        None => r.problem(1, 0, "no nodes"),
+    }
+    const SHAPE: &str = "NNN = (NNN, NNN)";
+    let mut defined = HashMap::new();
+    let mut references = Vec::new();
+    for (i, line) in lines {
+        let bad = SHAPE
+            .chars()
+            .zip_longest(line.chars())
+            .position(|pair| match pair {
+                EitherOrBoth::Both('N', c) => !c.is_ascii_alphanumeric(),
+                EitherOrBoth::Both(s, c) => s != c,
+                _ => true,
+            });
+        if let Some(j) = bad {
+            r.problem(i, j, format!("expected `{SHAPE}` with 3-character names"));
+            continue;
         }
-        let t = turns.chars().nth(cur_turn % turns.len()).unwrap();
-        cur_nodes.iter_mut().for_each(|n| {
-            *n = match t {
-                'L' => map[n].0,
-                'R' => map[n].1,
-                _ => panic!(r"¯\(◉◡◔)/¯"),
-            }
-        });
-        cur_turn += 1;
+        if let Some(first) = defined.insert(&line[0..3], i) {
+            r.problem(
+                i,
+                0,
+                format!("{} is already defined on line {}", &line[0..3], first + 1),
+            );
+        }
+        references.extend([(i, 7), (i, 12)].map(|(i, j)| (i, j, &line[j..j + 3])));
     }
-    zs.iter().fold(zs[0], |acc, v| num::integer::lcm(acc, *v))
+    references
+        .iter()
+        .filter(|(_, _, name)| !defined.contains_key(name))
+        .for_each(|(i, j, name)| r.problem(*i, *j, format!("{name} is never defined")));
+    r.stat("instructions", turns.len());
+    r.stat("nodes", defined.len());
 }
 
-fn solution(input: &str) -> (usize, usize) {
-    let turns = input.lines().next().unwrap();
-    let map: HashMap<&str, (&str, &str)> = input
-        .lines()
-        .skip(2)
-        .map(|l| (&l[0..3], (&l[7..10], &l[12..15])))
-        .collect();
-    (p1(turns, &map), p2(turns, &map))
+pub const VERSION: u32 = 2;
+
+pub fn part1(input: &str) -> Answer {
+    Network::from(input).p1().into()
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc8s.in").unwrap();
-    assert_eq!(solution(&input), (2, 2));
-    let input = fs::read_to_string("src/inputs/aoc8s2.in").unwrap();
-    assert_eq!(solution(&input), (6, 6));
-    let input = fs::read_to_string("src/inputs/aoc8s3.in").unwrap();
-    assert_eq!(solution(&input), (0, 6));
-    let input = fs::read_to_string("src/inputs/aoc8.in").unwrap();
-    assert_eq!(solution(&input), (13207, 12324145107121));
+pub fn part2(input: &str) -> Answer {
+    Network::from(input).p2().into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc8.in").unwrap();
-    let (p1, p2) = solution(&input);
-    (p1.to_string(), p2.to_string())
+#[test]
+fn test_validate() {
+    let mut r = Report::default();
+    validate(
+        "LRX\n\nAAA = (BBB, CCC)\nBBB = (AAA,ZZZ)\nAAA = (AAA, AAA)\nCCC = (Q-Q, AAA)\nZZZ = (ZZZ, DDD)\n",
+        &mut r,
+    );
+    let found = r.problems.iter().map(|p| (p.line, p.col, p.msg.as_str()));
+    assert_eq!(
+        found.collect_vec(),
+        [
+            (1, 3, "'X' is not a turn, expected L or R"),
+            (4, 12, "expected `NNN = (NNN, NNN)` with 3-character names"),
+            (5, 1, "AAA is already defined on line 3"),
+            (6, 9, "expected `NNN = (NNN, NNN)` with 3-character names"),
+            (3, 8, "BBB is never defined"),
+            (3, 13, "CCC is never defined"),
+            (7, 13, "DDD is never defined"),
+        ]
+    );
+    assert_eq!(
+        r.stats,
+        [
+            ("instructions", "3".to_string()),
+            ("nodes", "2".to_string())
+        ]
+    );
+    let mut r = Report::default();
+    validate("LR\nAAA = (AAA, AAA)\n", &mut r);
+    assert_eq!(
+        r.problems[0].msg,
+        "expected a blank line after the instructions"
+    );
 }
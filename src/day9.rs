@@ -1,55 +1,32 @@
-use std::fs;
-
 use itertools::Itertools;
 
-fn get_diffs(seq: &[i32]) -> Vec<i32> {
-    seq.windows(2).map(|w| w[1] - w[0]).collect_vec()
-}
+use crate::{answer::Answer, math, parse};
 
-fn predict(seq: &[i32]) -> (i32, i32) {
-    let mut d = seq.to_owned();
-    let mut last = Vec::new();
-    let mut first = Vec::new();
-    last.push(*d.last().unwrap());
-    first.push(*d.first().unwrap());
-    while !d.iter().all_equal() {
-        d = get_diffs(&d);
-        last.push(*d.last().unwrap());
-        first.push(*d.first().unwrap());
-    }
+/// The values before the first and after the last element of the sequence.
+fn predict(seq: &[i64]) -> (i64, i64) {
     (
-        first.iter().rev().fold(0, |acc, v| *v - acc),
-        last.iter().sum::<i32>(),
+        math::extrapolate(seq, -1, "day9 p2: extrapolate"),
+        math::extrapolate(seq, seq.len() as i64, "day9 p1: extrapolate"),
     )
 }
 
-fn solution(input: &str) -> (i32, i32) {
+fn solution(input: &str) -> (i64, i64) {
     let predictions = input
         .lines()
-        .map(|l| {
-            predict(
-                &l.split(' ')
-                    .map(|d| d.parse::<i32>().unwrap())
-                    .collect_vec(),
-            )
-        })
+        .map(|l| predict(&parse::ints::<i64>(l).unwrap()))
         .collect_vec();
     (
-        predictions.iter().map(|p| p.0).sum(),
-        predictions.iter().map(|p| p.1).sum(),
+        math::sum(predictions.iter().map(|p| p.1), "day9 p1: predictions"),
+        math::sum(predictions.iter().map(|p| p.0), "day9 p2: predictions"),
     )
 }
 
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aoc9s.in").unwrap();
-    assert_eq!(solution(&input), (2, 114));
-    let input = fs::read_to_string("src/inputs/aoc9.in").unwrap();
-    assert_eq!(solution(&input), (973, 1479011877));
+pub const VERSION: u32 = 3;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).0.into()
 }
 
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aoc9.in").unwrap();
-    let (p1, p2) = solution(&input);
-    (p1.to_string(), p2.to_string())
+pub fn part2(input: &str) -> Answer {
+    solution(input).1.into()
 }
@@ -0,0 +1,187 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::json;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    /// Wide enough for any `u64` or `i64` fact.
+    Int(i128),
+    Text(String),
+    List(Vec<Value>),
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+macro_rules! int_value {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Value {
+            fn from(n: $t) -> Self {
+                Self::Int(n.into())
+            }
+        }
+    )*};
+}
+
+int_value!(u32, u64, i64);
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Self::Int(n as i128)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Self::List(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Value {
+    fn to_json(&self) -> String {
+        match self {
+            Self::Bool(b) => b.to_string(),
+            Self::Int(n) => n.to_string(),
+            Self::Text(s) => json::string(s),
+            Self::List(v) => format!("[{}]", v.iter().map(Self::to_json).join(",")),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{}", if *b { "yes" } else { "no" }),
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+            Self::List(v) => write!(f, "[{}]", v.iter().join(", ")),
+        }
+    }
+}
+
+/// What one `subject` (a game, a hand, a pattern, ...) contributed to the answer
+/// of `part`, as named facts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub part: usize,
+    pub subject: String,
+    pub facts: Vec<(&'static str, Value)>,
+}
+
+impl Entry {
+    pub fn new(part: usize, subject: impl Into<String>) -> Self {
+        Self {
+            part,
+            subject: subject.into(),
+            facts: Vec::new(),
+        }
+    }
+
+    pub fn fact(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.facts.push((name, value.into()));
+        self
+    }
+}
+
+/// Receiver of a solver's derivation. `make` is only called if the entry is
+/// actually kept, so with [`NoExplain`] the hook compiles down to nothing.
+pub trait Explain {
+    fn entry(&mut self, make: impl FnOnce() -> Entry);
+}
+
+/// Explanations disabled; what every solver uses outside of `run --explain`.
+pub struct NoExplain;
+
+impl Explain for NoExplain {
+    #[inline(always)]
+    fn entry(&mut self, _make: impl FnOnce() -> Entry) {}
+}
+
+/// Collects the entries to print them as text or JSON.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub entries: Vec<Entry>,
+}
+
+impl Explain for Report {
+    fn entry(&mut self, make: impl FnOnce() -> Entry) {
+        self.entries.push(make());
+    }
+}
+
+impl Report {
+    /// One line per entry, grouped under a header per part.
+    pub fn to_text(&self) -> String {
+        self.entries
+            .chunk_by(|a, b| a.part == b.part)
+            .map(|entries| {
+                let lines = entries.iter().map(|e| {
+                    let facts = e.facts.iter().map(|(k, v)| format!("{k} = {v}"));
+                    format!("  {}: {}\n", e.subject, facts.format(", "))
+                });
+                format!("part {}\n{}", entries[0].part, lines.format(""))
+            })
+            .join("")
+    }
+
+    /// A JSON array with one object per entry, its facts next to `part` and `subject`.
+    pub fn to_json(&self) -> String {
+        let entries = self.entries.iter().map(|e| {
+            let facts = e
+                .facts
+                .iter()
+                .map(|(k, v)| format!(",{}:{}", json::string(k), v.to_json()));
+            format!(
+                "{{\"part\":{},\"subject\":{}{}}}",
+                e.part,
+                json::string(&e.subject),
+                facts.format("")
+            )
+        });
+        format!("[{}]", entries.format(","))
+    }
+}
+
+#[test]
+fn test_explain() {
+    let mut r = Report::default();
+    r.entry(|| {
+        Entry::new(1, "game 3")
+            .fact("violations", vec!["20 red > 12"])
+            .fact("counted", false)
+    });
+    r.entry(|| Entry::new(2, "game 3").fact("power", 1560u32));
+    r.entry(|| Entry::new(2, "big").fact("n", u64::MAX));
+    assert_eq!(
+        r.to_text(),
+        "part 1\n  game 3: violations = [20 red > 12], counted = no\npart 2\n  game 3: power = 1560\n  big: n = 18446744073709551615\n"
+    );
+    assert_eq!(
+        r.to_json(),
+        "[{\"part\":1,\"subject\":\"game 3\",\"violations\":[\"20 red > 12\"],\"counted\":false},{\"part\":2,\"subject\":\"game 3\",\"power\":1560},{\"part\":2,\"subject\":\"big\",\"n\":18446744073709551615}]"
+    );
+    let mut called = false;
+    NoExplain.entry(|| {
+        called = true;
+        Entry::new(1, "x")
+    });
+    assert!(!called);
+}
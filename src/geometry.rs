@@ -0,0 +1,146 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Integer 2D point; `y` grows downwards, like the rows of puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Points double as displacement vectors.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Component-wise sign, i.e. the unit step from the origin towards `self`.
+    pub fn signum(&self) -> Vec2 {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn step(&self, dir: Direction) -> Self {
+        *self + dir.offset()
+    }
+
+    pub fn step_by(&self, dir: Direction, n: i32) -> Self {
+        *self + dir.offset() * n
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from up.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_left(&self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Left => Self::Down,
+            Self::Down => Self::Right,
+            Self::Right => Self::Up,
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        match self {
+            Self::Up => Point::new(0, -1),
+            Self::Right => Point::new(1, 0),
+            Self::Down => Point::new(0, 1),
+            Self::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+#[test]
+fn test_geometry() {
+    let p = Point::new(2, 3);
+    assert_eq!(p.step(Direction::Up), Point::new(2, 2));
+    assert_eq!(p.step_by(Direction::Left, 4), Point::new(-2, 3));
+    assert_eq!(p - Point::new(5, 5), Point::new(-3, -2));
+    assert_eq!((Point::new(0, -7) * 2).signum(), Direction::Up.offset());
+    assert_eq!(p.manhattan(&Point::new(-1, 7)), 7);
+    Direction::ALL.iter().for_each(|d| {
+        assert_eq!(d.turn_left().turn_right(), *d);
+        assert_eq!(d.turn_right().turn_right(), d.reverse());
+        assert_eq!(d.offset() + d.reverse().offset(), Point::default());
+    });
+}
@@ -0,0 +1,198 @@
+use core::fmt;
+use std::{collections::HashMap, fmt::Write, hash::Hash};
+
+use itertools::Itertools;
+use num::Integer;
+
+use crate::{cycle, math};
+
+pub type NodeId = usize;
+
+/// Directed graph with string-named nodes interned to dense ids and labelled edges.
+/// Edges keep their insertion order, so a node's successors can be indexed.
+#[derive(Debug, Clone)]
+pub struct Graph<E> {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    edges: Vec<Vec<(NodeId, E)>>,
+}
+
+impl<E> Default for Graph<E> {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<E> Graph<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of the node called `name`, adding the node if it is new.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        self.ids.insert(name.to_string(), self.names.len());
+        self.names.push(name.to_string());
+        self.edges.push(Vec::new());
+        self.names.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, label: E) {
+        let (from, to) = (self.intern(from), self.intern(to));
+        self.edges[from].push((to, label));
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len()
+    }
+
+    pub fn successors(&self, id: NodeId) -> &[(NodeId, E)] {
+        &self.edges[id]
+    }
+}
+
+impl<E: fmt::Display> Graph<E> {
+    /// Graphviz rendering of the graph, e.g. `dot -Tsvg` friendly.
+    pub fn to_dot(&self, title: &str) -> String {
+        let mut r = format!("digraph \"{title}\" {{\n");
+        self.nodes().for_each(|from| {
+            self.successors(from).iter().for_each(|(to, label)| {
+                writeln!(
+                    r,
+                    "    \"{}\" -> \"{}\" [label=\"{label}\"];",
+                    self.name(from),
+                    self.name(*to)
+                )
+                .unwrap()
+            })
+        });
+        r.push_str("}\n");
+        r
+    }
+}
+
+/// Shape of a deterministic walk: `prefix` states are visited once, after which the
+/// walk repeats with period `cycle`. `hits` are the times `t < prefix + cycle` at
+/// which the walk is on a target state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    pub prefix: usize,
+    pub cycle: usize,
+    pub hits: Vec<usize>,
+}
+
+impl Trajectory {
+    pub fn new<S: Hash + Eq + Clone>(
+        start: S,
+        step: impl FnMut(&S) -> S,
+        mut is_target: impl FnMut(&S) -> bool,
+    ) -> Self {
+        let (history, cycle) = cycle::walk(start, step, usize::MAX);
+        let (prefix, cycle) = cycle.unwrap();
+        Self {
+            prefix,
+            cycle,
+            hits: history.iter().positions(&mut is_target).collect(),
+        }
+    }
+
+    /// Whether the walk is on a target at time `t`.
+    pub fn hits_at(&self, t: usize) -> bool {
+        if t < self.prefix + self.cycle {
+            self.hits.contains(&t)
+        } else {
+            self.hits
+                .iter()
+                .any(|h| *h >= self.prefix && (t - h).is_multiple_of(self.cycle))
+        }
+    }
+
+    /// True when the target times are exactly the multiples of the cycle length,
+    /// the case where the first hit times can simply be combined with an LCM.
+    pub fn is_simple(&self) -> bool {
+        self.hits == [self.cycle]
+    }
+}
+
+/// Earliest time at which every trajectory is on a target, if there is one.
+pub fn first_common_hit(trajectories: &[Trajectory]) -> Option<usize> {
+    let once = trajectories
+        .iter()
+        .flat_map(|tr| tr.hits.iter().filter(|h| **h < tr.prefix))
+        .filter(|t| trajectories.iter().all(|tr| tr.hits_at(**t)))
+        .min()
+        .copied();
+    let lo = trajectories.iter().map(|tr| tr.prefix).max().unwrap_or(0) as i128;
+    let periodic = trajectories
+        .iter()
+        .map(|tr| {
+            tr.hits
+                .iter()
+                .filter(|h| **h >= tr.prefix)
+                .map(|h| (*h as i128 % tr.cycle as i128, tr.cycle as i128))
+                .collect_vec()
+        })
+        .multi_cartesian_product()
+        .filter_map(math::crt_all)
+        .map(|(r, m)| r + Integer::div_ceil(&(lo - r).max(0), &m) * m)
+        .min()
+        .map(|t| t as usize);
+    once.into_iter().chain(periodic).min()
+}
+
+#[test]
+fn test_graph() {
+    let mut g = Graph::new();
+    g.add_edge("a", "b", 'L');
+    g.add_edge("a", "c", 'R');
+    g.add_edge("b", "a", 'L');
+    assert_eq!((g.len(), g.id("c"), g.id("d")), (3, Some(2), None));
+    assert_eq!(g.successors(g.id("a").unwrap()), [(1, 'L'), (2, 'R')]);
+    assert_eq!(g.name(1), "b");
+    assert_eq!(
+        g.to_dot("g"),
+        "digraph \"g\" {\n    \"a\" -> \"b\" [label=\"L\"];\n    \"a\" -> \"c\" [label=\"R\"];\n    \"b\" -> \"a\" [label=\"L\"];\n}\n"
+    );
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 2, targets on even numbers
+    let tr = Trajectory::new(0, |n| if *n == 4 { 2 } else { n + 1 }, |n| n % 2 == 0);
+    assert_eq!(
+        tr,
+        Trajectory {
+            prefix: 2,
+            cycle: 3,
+            hits: vec![0, 2, 4]
+        }
+    );
+    assert!(tr.hits_at(7) && tr.hits_at(8) && !tr.hits_at(9) && !tr.is_simple());
+    // a start node leading into a loop of length c through a single target
+    let every = |c: usize| Trajectory::new(c, move |n| (n + 1) % c, |n| *n == 0);
+    assert!(every(4).is_simple());
+    assert_eq!(first_common_hit(&[every(4), every(6)]), Some(12));
+    assert_eq!(first_common_hit(&[tr.clone(), every(5)]), Some(5));
+    assert_eq!(first_common_hit(&[tr]), Some(0));
+    let odd = Trajectory {
+        prefix: 0,
+        cycle: 2,
+        hits: vec![1],
+    };
+    assert_eq!(first_common_hit(&[odd, every(4)]), None);
+}
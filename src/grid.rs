@@ -0,0 +1,218 @@
+use core::fmt;
+use std::ops::{Index, IndexMut};
+
+use itertools::Itertools;
+
+use crate::geometry::Point;
+
+const OFFSETS8: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Row-major 2D grid backed by a single contiguous `Vec`, indexed by `(x, y)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| f(x, y))
+                .collect(),
+        }
+    }
+
+    /// Parses one row per line, mapping every character through `f`.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> T) -> Self {
+        Self::parse_indexed(s, |_, _, c| f(c))
+    }
+
+    /// Like [`Grid::parse`], but the mapper also gets the `(x, y)` of the character.
+    pub fn parse_indexed(s: &str, mut f: impl FnMut(usize, usize, char) -> T) -> Self {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(s.len());
+        s.lines().enumerate().for_each(|(y, l)| {
+            let len = cells.len();
+            cells.extend(l.chars().enumerate().map(|(x, c)| f(x, y, c)));
+            if y == 0 {
+                width = cells.len();
+            } else if cells.len() - len != width {
+                panic!("(ノ°Д°）ノ︵ ┻━┻ ragged grid row {y}");
+            }
+            height += 1;
+        });
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_grid(&self, p: Point) -> bool {
+        p.x >= 0 && (p.x as usize) < self.width && p.y >= 0 && (p.y as usize) < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.in_grid(p) {
+            Some(&self.cells[p.y as usize * self.width + p.x as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.in_grid(p) {
+            Some(&mut self.cells[p.y as usize * self.width + p.x as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.positions()
+            .map(|(x, y)| Point::new(x as i32, y as i32))
+    }
+
+    /// Iterates over all cells together with their `(x, y)`.
+    pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    fn neighbours<'a>(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(i32, i32)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width as i32, self.height as i32);
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if nx >= 0 && nx < width && ny >= 0 && ny < height {
+                Some((nx as usize, ny as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Orthogonal and diagonal neighbours of `(x, y)` that lie inside the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &OFFSETS8)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(x < self.width, "x {x} out of grid width {}", self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        assert!(x < self.width, "x {x} out of grid width {}", self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        &self[(p.x as usize, p.y as usize)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        &mut self[(p.x as usize, p.y as usize)]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.rows().map(|row| row.iter().join("")).join("\n")
+        )
+    }
+}
+
+#[test]
+fn test_grid() {
+    let g = Grid::parse("abc\ndef\n", |c| c);
+    assert_eq!((g.width(), g.height()), (3, 2));
+    assert_eq!(g[(2, 1)], 'f');
+    assert_eq!(g.get(Point::new(-1, 0)), None);
+    assert_eq!(g.get(Point::new(1, 1)), Some(&'e'));
+    assert_eq!(g[Point::new(0, 1)], 'd');
+    assert_eq!(g.to_string(), "abc\ndef");
+    assert_eq!(g.transpose().to_string(), "ad\nbe\ncf");
+    assert_eq!(g.column(1).collect::<String>(), "be");
+    assert_eq!(g.neighbours8(1, 1).count(), 5);
+}
@@ -0,0 +1,120 @@
+use std::fs;
+
+use itertools::Itertools;
+
+pub const INPUTS_DIR: &str = "src/inputs";
+
+/// Puzzle inputs compiled into the binary by the `embed-inputs` feature, so it
+/// runs from any directory. Samples are only needed by tests and stay on disk.
+#[cfg(feature = "embed-inputs")]
+const EMBEDDED: &[(&str, &str)] = &[
+    ("aoc1.in", include_str!("inputs/aoc1.in")),
+    ("aoc2.in", include_str!("inputs/aoc2.in")),
+    ("aoc3.in", include_str!("inputs/aoc3.in")),
+    ("aoc4.in", include_str!("inputs/aoc4.in")),
+    ("aoc5.in", include_str!("inputs/aoc5.in")),
+    ("aoc6.in", include_str!("inputs/aoc6.in")),
+    ("aoc7.in", include_str!("inputs/aoc7.in")),
+    ("aoc8.in", include_str!("inputs/aoc8.in")),
+    ("aoc9.in", include_str!("inputs/aoc9.in")),
+    ("aoc10.in", include_str!("inputs/aoc10.in")),
+    ("aoc11.in", include_str!("inputs/aoc11.in")),
+    ("aoc12.in", include_str!("inputs/aoc12.in")),
+    ("aoc13.in", include_str!("inputs/aoc13.in")),
+    ("aoc14.in", include_str!("inputs/aoc14.in")),
+    ("aoc15.in", include_str!("inputs/aoc15.in")),
+    ("aoc16.in", include_str!("inputs/aoc16.in")),
+    ("aoc17.in", include_str!("inputs/aoc17.in")),
+];
+
+#[cfg(feature = "embed-inputs")]
+fn embedded(name: &str) -> Option<&'static str> {
+    EMBEDDED.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+fn embedded(_name: &str) -> Option<&'static str> {
+    None
+}
+
+/// Brings raw puzzle input into the canonical form every day expects: no BOM,
+/// `\n` line endings, no trailing whitespace and exactly one final newline.
+/// Returns the normalized text together with a list of what had to be changed;
+/// a missing final newline is benign and added without being listed.
+pub fn normalize(raw: &str) -> (String, Vec<&'static str>) {
+    let mut changes = Vec::new();
+    let s = match raw.strip_prefix('\u{feff}') {
+        Some(s) => {
+            changes.push("stripped UTF-8 BOM");
+            s
+        }
+        None => raw,
+    };
+    if s.contains("\r\n") {
+        changes.push("converted CRLF line endings");
+    }
+    let lines = s.lines().collect_vec();
+    let trimmed = lines.iter().map(|l| l.trim_end()).collect_vec();
+    if lines != trimmed {
+        changes.push("trimmed trailing whitespace");
+    }
+    let joined = trimmed.join("\n");
+    let body = joined.trim_end_matches('\n');
+    if body.is_empty() {
+        return (String::new(), changes);
+    }
+    if body.len() < joined.len() {
+        changes.push("removed trailing blank lines");
+    }
+    (format!("{body}\n"), changes)
+}
+
+fn normalize_warn(name: &str, raw: &str) -> String {
+    let (input, changes) = normalize(raw);
+    if !changes.is_empty() {
+        eprintln!("warning: {name}: {}", changes.join(", "));
+    }
+    input
+}
+
+/// Reads input `name` from the default inputs directory, see [`read_in`]; the
+/// runner goes through the configured directory instead.
+#[cfg(test)]
+pub fn read(name: &str) -> String {
+    read_in(INPUTS_DIR, name)
+}
+
+/// Reads input `name` from `dir`, or from the binary if it was embedded and `dir`
+/// is the default, normalizing it and warning on stderr when it was not already
+/// in canonical form.
+pub fn read_in(dir: &str, name: &str) -> String {
+    match embedded(name).filter(|_| dir == INPUTS_DIR) {
+        Some(raw) => normalize_warn(name, raw),
+        None => read_file(&format!("{dir}/{name}")),
+    }
+}
+
+/// Reads and normalizes an input file at an arbitrary path, e.g. from `--input`.
+pub fn read_file(path: &str) -> String {
+    let raw = fs::read_to_string(path).unwrap_or_else(|e| panic!("(╥﹏╥) {path}: {e}"));
+    normalize_warn(path, &raw)
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("a\nb\n"), ("a\nb\n".to_string(), vec![]));
+    assert_eq!(normalize("a\nb"), ("a\nb\n".to_string(), vec![]));
+    assert_eq!(
+        normalize("\u{feff}a \r\nb\t\r\n\r\n\n"),
+        (
+            "a\nb\n".to_string(),
+            vec![
+                "stripped UTF-8 BOM",
+                "converted CRLF line endings",
+                "trimmed trailing whitespace",
+                "removed trailing blank lines"
+            ]
+        )
+    );
+    assert_eq!(normalize(""), (String::new(), vec![]));
+}
@@ -0,0 +1,158 @@
+use itertools::Itertools;
+
+/// Half-open integer interval `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    pub fn from_len(start: i64, len: i64) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, v: i64) -> bool {
+        (self.start..self.end).contains(&v)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let r = Self::new(self.start.max(other.start), self.end.min(other.end));
+        if r.is_empty() {
+            None
+        } else {
+            Some(r)
+        }
+    }
+
+    pub fn shift(&self, delta: i64) -> Self {
+        Self::new(self.start + delta, self.end + delta)
+    }
+}
+
+/// Set of integers stored as sorted, disjoint, non-adjacent intervals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut r = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            r.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: r }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut r = Vec::new();
+        let mut j = 0;
+        for a in self.iter() {
+            let mut start = a.start;
+            while j < other.intervals.len() && other.intervals[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start < a.end {
+                let b = &other.intervals[k];
+                if b.start > start {
+                    r.push(Interval::new(start, b.start));
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+            if start < a.end {
+                r.push(Interval::new(start, a.end));
+            }
+        }
+        Self { intervals: r }
+    }
+
+    pub fn shift(&self, delta: i64) -> Self {
+        Self {
+            intervals: self.iter().map(|i| i.shift(delta)).collect(),
+        }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        [interval].into_iter().collect()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let intervals = iter
+            .into_iter()
+            .filter(|i| !i.is_empty())
+            .sorted()
+            .coalesce(|a, b| {
+                if b.start <= a.end {
+                    Ok(Interval::new(a.start, a.end.max(b.end)))
+                } else {
+                    Err((a, b))
+                }
+            })
+            .collect();
+        Self { intervals }
+    }
+}
+
+#[test]
+fn test_interval_set() {
+    let a: IntervalSet = [
+        Interval::new(0, 5),
+        Interval::new(3, 8),
+        Interval::new(10, 12),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        a.iter().copied().collect_vec(),
+        [Interval::new(0, 8), Interval::new(10, 12)]
+    );
+    assert_eq!(a.min(), Some(0));
+    assert!(Interval::new(0, 8).contains(7) && !Interval::new(0, 8).contains(8));
+    let b = IntervalSet::from(Interval::new(6, 11));
+    assert_eq!(
+        a.intersection(&b).iter().copied().collect_vec(),
+        [Interval::new(6, 8), Interval::new(10, 11)]
+    );
+    assert_eq!(
+        a.difference(&b).iter().copied().collect_vec(),
+        [Interval::new(0, 6), Interval::new(11, 12)]
+    );
+    assert_eq!(a.union(&b), IntervalSet::from(Interval::new(0, 12)));
+    assert_eq!(b.shift(-6), IntervalSet::from(Interval::from_len(0, 5)));
+    let c = IntervalSet::from(Interval::new(4, 5)).union(&Interval::new(5, 6).into());
+    assert_eq!(c, IntervalSet::from(Interval::new(4, 6)));
+}
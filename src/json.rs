@@ -0,0 +1,19 @@
+/// JSON string literal for `s`.
+pub fn string(s: &str) -> String {
+    let mut r = String::from("\"");
+    s.chars().for_each(|c| match c {
+        '"' => r.push_str("\\\""),
+        '\\' => r.push_str("\\\\"),
+        '\n' => r.push_str("\\n"),
+        c if (c as u32) < 0x20 => r.push_str(&format!("\\u{:04x}", c as u32)),
+        c => r.push(c),
+    });
+    r.push('"');
+    r
+}
+
+#[test]
+fn test_json() {
+    assert_eq!(string("a\"b\\\n\t"), "\"a\\\"b\\\\\\n\\u0009\"");
+    assert_eq!(string("día"), "\"día\"");
+}
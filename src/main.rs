@@ -2,11 +2,26 @@
 #![feature(iter_next_chunk)]
 #![feature(cmp_minmax)]
 #![feature(iter_advance_by)]
-#![feature(btree_cursors)]
 
+use answer::Answer;
+use cache::Cache;
+use config::Config;
+use itertools::Itertools;
 use rayon::prelude::*;
-use std::{env, time::Instant};
+use std::{
+    env, fs,
+    net::TcpListener,
+    panic,
+    path::Path,
+    time::{Duration, Instant},
+};
 
+mod animate;
+mod answer;
+mod bitgrid;
+mod cache;
+mod config;
+mod cycle;
 mod day1;
 mod day10;
 mod day11;
@@ -24,47 +39,399 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod explain;
+mod geometry;
+mod graph;
+mod grid;
+mod input;
+mod interval;
+mod json;
+mod math;
+mod memo;
+mod parse;
+mod polygon;
+mod progress;
+mod render;
+#[cfg(test)]
+mod samples;
+mod search;
+mod serve;
+mod validate;
 
-fn main() {
-    let days = [
-        (5, day5::run as fn() -> (String, String)),
-        (1, day1::run),
-        (2, day2::run),
-        (3, day3::run),
-        (4, day4::run),
-        (6, day6::run),
-        (7, day7::run),
-        (8, day8::run),
-        (9, day9::run),
-        (10, day10::run),
-        (11, day11::run),
-        (12, day12::run),
-        (13, day13::run),
-        (14, day14::run),
-        (15, day15::run),
-        (16, day16::run),
-        (17, day17::run),
+type Part = fn(&str) -> Answer;
+type PartWithProgress = fn(&str, &progress::Bar) -> Answer;
+type Explainer = fn(&str, &mut explain::Report) -> [Answer; 2];
+type Validator = fn(&str, &mut validate::Report);
+
+/// Part 1 and part 2 solvers of every day, with the day's solver version that keys
+/// cached answers; bump it whenever the day's code changes.
+const DAYS: [(usize, [Part; 2], u32); 17] = [
+    (1, [day1::part1, day1::part2], day1::VERSION),
+    (2, [day2::part1, day2::part2], day2::VERSION),
+    (3, [day3::part1, day3::part2], day3::VERSION),
+    (4, [day4::part1, day4::part2], day4::VERSION),
+    (5, [day5::part1, day5::part2], day5::VERSION),
+    (6, [day6::part1, day6::part2], day6::VERSION),
+    (7, [day7::part1, day7::part2], day7::VERSION),
+    (8, [day8::part1, day8::part2], day8::VERSION),
+    (9, [day9::part1, day9::part2], day9::VERSION),
+    (10, [day10::part1, day10::part2], day10::VERSION),
+    (11, [day11::part1, day11::part2], day11::VERSION),
+    (12, [day12::part1, day12::part2], day12::VERSION),
+    (13, [day13::part1, day13::part2], day13::VERSION),
+    (14, [day14::part1, day14::part2], day14::VERSION),
+    (15, [day15::part1, day15::part2], day15::VERSION),
+    (16, [day16::part1, day16::part2], day16::VERSION),
+    (17, [day17::part1, day17::part2], day17::VERSION),
+];
+
+/// Alternative solvers as `(day, part, name, solver)`; the `DAYS` ones are "default".
+const VARIANTS: [(usize, usize, &str, Part); 4] = [
+    (6, 1, "brute", day6::part1_brute),
+    (6, 2, "brute", day6::part2_brute),
+    (10, 2, "scanline", day10::part2_scanline),
+    (12, 1, "backtrack", day12::part1_backtrack),
+];
+
+/// Default solvers that can report progress, as `(day, part, solver)`.
+const PROGRESS: [(usize, usize, PartWithProgress); 3] = [
+    (12, 1, day12::part1_with_progress),
+    (12, 2, day12::part2_with_progress),
+    (16, 2, day16::part2_with_progress),
+];
+
+/// Days that can explain their answers.
+const EXPLAIN: [(usize, Explainer); 4] = [
+    (2, day2::explain),
+    (4, day4::explain),
+    (7, day7::explain),
+    (13, day13::explain),
+];
+
+/// Days that can check their input's format without solving.
+const VALIDATE: [(usize, Validator); 5] = [
+    (7, day7::validate),
+    (8, day8::validate),
+    (14, day14::validate),
+    (16, day16::validate),
+    (17, day17::validate),
+];
+
+/// Every solver for `part` of `day`, the default one first.
+fn variants(day: usize, part: usize) -> Vec<(&'static str, Part)> {
+    DAYS.iter()
+        .filter(|d| d.0 == day)
+        .map(|d| ("default", d.1[part - 1]))
+        .chain(
+            VARIANTS
+                .iter()
+                .filter(|v| v.0 == day && v.1 == part)
+                .map(|v| (v.2, v.3)),
+        )
+        .collect()
+}
+
+/// Solver for `part` of `day` called `variant`, falling back to the default, with
+/// the name of the one picked.
+fn solver(day: usize, part: usize, variant: &str) -> (&'static str, Part) {
+    let variants = variants(day, part);
+    *variants
+        .iter()
+        .find(|v| v.0 == variant)
+        .unwrap_or(&variants[0])
+}
+
+/// Runs a solver, reporting an integer overflow it hits as `dayN ...: <operation>:
+/// overflow` on stderr and the part as unsolved instead of crashing.
+fn checked(solve: impl FnOnce() -> Answer) -> Answer {
+    math::catch_overflow(solve).unwrap_or_else(|overflow| {
+        eprintln!("{overflow}");
+        Answer::Unsolved("overflow".to_string())
+    })
+}
+
+/// Value following the flag `name`, e.g. `--out DIR`.
+fn opt<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Puzzle input for `day`: the file given with `--input`, or the day's own input.
+fn day_input(args: &[String], config: &Config, day: usize) -> String {
+    match opt(args, "--input") {
+        Some(path) => input::read_file(path),
+        None => input::read_in(&config.inputs, &format!("aoc{day}.in")),
+    }
+}
+
+/// `render [day] [--input FILE] [--out DIR] [--format png|ppm|svg] [--scale N]`:
+/// writes an image per day that supports it, or just for `day` if given.
+fn render(args: &[String], config: &Config) {
+    let renderers = [
+        (10, day10::render as fn(&str) -> render::Image),
+        (14, day14::render),
+        (16, day16::render),
+        (17, day17::render),
     ];
+    let (out, format) = (&config.render_out, &config.render_format);
+    let day = args.first().and_then(|a| a.parse::<usize>().ok());
+    if day.is_none() && opt(args, "--input").is_some() {
+        eprintln!("--input needs a day to render it with");
+        return;
+    }
+    if let Some(day) = day.filter(|d| !renderers.iter().any(|r| r.0 == *d)) {
+        eprintln!("day{day} has nothing to render");
+        return;
+    }
+    fs::create_dir_all(out).unwrap();
+    renderers
+        .par_iter()
+        .filter(|(d, _)| day.is_none_or(|day| day == *d))
+        .for_each(|(d, f)| {
+            let path = Path::new(out).join(format!("day{d}.{format}"));
+            match f(&day_input(args, config, *d))
+                .scale(config.render_scale)
+                .save(&path)
+            {
+                Ok(()) => println!("wrote {}", path.display()),
+                Err(e) => eprintln!("day{d}: {e}"),
+            }
+        });
+}
+
+/// `run [day] [--input FILE] [--variant NAME] [--no-cache] [--output text|json] [--json]
+/// [--dot] [--explain] [--animate [--delay MS]]`: solves one day, or all of them in
+/// parallel, reusing cached answers unless `--no-cache` is given. JSON output, which
+/// `--json` is short for, is one object per day.
+fn run(args: &[String], config: &Config) {
     let now = Instant::now();
-    let day = env::args()
-        .nth(1)
-        .unwrap_or("0".to_string())
-        .parse::<usize>()
+    let json = config.run_format == "json" || args.iter().any(|a| a == "--json");
+    let day = args
+        .first()
+        .and_then(|a| a.parse::<usize>().ok())
         .unwrap_or(0);
-    match day {
-        1..=25 => {
-            let (p1, p2) = days[day - 1].1();
-            println!("day{day} p1: {p1}\nday{day} p2: {p2}");
+    if args.iter().any(|a| a == "--dot") {
+        match day {
+            8 => print!("{}", day8::dot(&day_input(args, config, day))),
+            _ => eprintln!("day{day} has no graph to export"),
         }
-        _ => days.par_iter().for_each(|day| {
-            let now = Instant::now();
-            let (p1, p2) = day.1();
+        return;
+    }
+    if args.iter().any(|a| a == "--explain") {
+        let Some((_, f)) = EXPLAIN.iter().find(|e| e.0 == day) else {
+            return eprintln!("day{day} has no explanation");
+        };
+        let input = day_input(args, config, day);
+        let mut report = explain::Report::default();
+        let [p1, p2] = math::catch_overflow(|| f(&input, &mut report)).unwrap_or_else(|overflow| {
+            eprintln!("{overflow}");
+            [(); 2].map(|_| Answer::Unsolved("overflow".to_string()))
+        });
+        if json {
             println!(
-                "day{day_n} p1: {p1}\nday{day_n} p2: {p2}\nday{day_n} execution time: {:?}",
-                now.elapsed(),
-                day_n = day.0
+                "{{\"day\":{day},\"p1\":{},\"p2\":{},\"explanation\":{}}}",
+                p1.to_json(),
+                p2.to_json(),
+                report.to_json()
             );
+        } else {
+            print!("{}day{day} p1: {p1}\nday{day} p2: {p2}\n", report.to_text());
+        }
+        return;
+    }
+    if args.iter().any(|a| a == "--animate") {
+        let delay = opt(args, "--delay")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(30);
+        let mut t = animate::Terminal::new(Duration::from_millis(delay));
+        match day {
+            14 => day14::animate(&day_input(args, config, day), &mut t),
+            16 => day16::animate(&day_input(args, config, day), &mut t),
+            17 => day17::animate(&day_input(args, config, day), &mut t),
+            _ => eprintln!("day{day} has no animation"),
+        }
+    }
+    let variant = opt(args, "--variant").unwrap_or("default");
+    if variant != "default"
+        && !VARIANTS
+            .iter()
+            .any(|v| v.2 == variant && (day == 0 || v.0 == day))
+    {
+        eprintln!("no variant {variant} for day{day}");
+        return;
+    }
+    let cache = (!args.iter().any(|a| a == "--no-cache")).then(|| Cache::new(&config.cache));
+    // only a single day gets progress bars, parallel days would draw over each other
+    let solve = |day: usize, input: &str, progress: bool| {
+        let version = DAYS.iter().find(|d| d.0 == day).unwrap().2;
+        let input_hash = cache::hash(input);
+        [1, 2].map(|part| {
+            let (variant, f) = solver(day, part, variant);
+            let with_progress = PROGRESS
+                .iter()
+                .find(|p| (p.0, p.1) == (day, part))
+                .filter(|_| progress && variant == "default");
+            let solve = || {
+                checked(|| match with_progress {
+                    Some((_, _, f)) => {
+                        let bar = progress::Bar::new(&format!("day{day} p{part}"));
+                        let r = f(input, &bar);
+                        bar.finish();
+                        r
+                    }
+                    None => f(input),
+                })
+            };
+            let key = cache::Key {
+                day,
+                part,
+                variant,
+                input_hash,
+                version,
+            };
+            match &cache {
+                Some(cache) => cache.get_or_solve(&key, solve),
+                None => solve(),
+            }
+        })
+    };
+    match DAYS.iter().find(|d| d.0 == day) {
+        Some(_) => {
+            let [p1, p2] = solve(day, &day_input(args, config, day), true);
+            if json {
+                println!(
+                    "{{\"day\":{day},\"p1\":{},\"p2\":{}}}",
+                    p1.to_json(),
+                    p2.to_json()
+                );
+            } else {
+                println!("day{day} p1: {p1}\nday{day} p2: {p2}");
+            }
+        }
+        None if opt(args, "--input").is_some() => {
+            eprintln!("--input needs a day to run it with");
+            return;
+        }
+        None => DAYS.par_iter().for_each(|(day_n, _, _)| {
+            let now = Instant::now();
+            let [p1, p2] = solve(*day_n, &day_input(&[], config, *day_n), false);
+            if json {
+                println!(
+                    "{{\"day\":{day_n},\"p1\":{},\"p2\":{},\"time_us\":{}}}",
+                    p1.to_json(),
+                    p2.to_json(),
+                    now.elapsed().as_micros()
+                );
+            } else {
+                println!(
+                    "day{day_n} p1: {p1}\nday{day_n} p2: {p2}\nday{day_n} execution time: {:?}",
+                    now.elapsed()
+                );
+            }
         }),
     }
-    println!("total execution time: {:?}", now.elapsed());
+    if !json {
+        println!("total execution time: {:?}", now.elapsed());
+    }
+}
+
+/// `compare <day> [--input FILE]`: runs every variant of both parts on the same
+/// input and prints their answers and timings side by side.
+fn compare(args: &[String], config: &Config) {
+    let Some(day) = args
+        .first()
+        .and_then(|a| a.parse::<usize>().ok())
+        .filter(|d| DAYS.iter().any(|x| x.0 == *d))
+    else {
+        eprintln!("compare needs a day");
+        return;
+    };
+    let input = day_input(args, config, day);
+    (1..=2).for_each(|part| {
+        let results = variants(day, part)
+            .into_iter()
+            .map(|(name, f)| {
+                let now = Instant::now();
+                let answer = checked(|| f(&input));
+                (
+                    name,
+                    answer.to_string(),
+                    answer,
+                    format!("{:?}", now.elapsed()),
+                )
+            })
+            .collect_vec();
+        let name_w = results.iter().map(|r| r.0.len()).max().unwrap_or(0);
+        let answer_w = results.iter().map(|r| r.1.len()).max().unwrap_or(0);
+        results.iter().for_each(|(name, answer, _, time)| {
+            println!("day{day} p{part}  {name:<name_w$}  {answer:>answer_w$}  {time:>12}")
+        });
+        if !results.iter().map(|r| &r.2).all_equal() {
+            println!("day{day} p{part}: variants disagree (╯°□°)╯︵ ┻━┻");
+        }
+    });
+}
+
+/// `validate <day> [--input FILE]`: reports every format problem in the input with
+/// its line and column, and some stats about it, exiting with 1 if there are any.
+fn validate(args: &[String], config: &Config) {
+    let day = args.first().and_then(|a| a.parse::<usize>().ok());
+    let Some((day, f)) = VALIDATE.iter().find(|v| Some(v.0) == day) else {
+        return eprintln!(
+            "validate needs a day with a validator: {}",
+            VALIDATE.iter().map(|v| v.0).join(", ")
+        );
+    };
+    let input = day_input(args, config, *day);
+    let mut report = validate::Report::default();
+    report.stat("lines", input.lines().count());
+    f(&input, &mut report);
+    print!("{report}");
+    if !report.is_ok() {
+        std::process::exit(1);
+    }
+}
+
+/// `serve [--port N] [--timeout SECS]`: HTTP API for the solvers on localhost.
+fn serve(config: &Config) {
+    let port = config.serve_port;
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(l) => l,
+        Err(e) => return eprintln!("can't listen on port {port}: {e}"),
+    };
+    println!("listening on http://127.0.0.1:{port}");
+    serve::serve(listener, Duration::from_secs(config.serve_timeout));
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = match Config::load(&args) {
+        Ok(c) => c,
+        Err(e) => return eprintln!("config: {e}"),
+    };
+    // overflows are reported by `checked`, without the panic message
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !info.payload().is::<math::Overflow>() {
+            hook(info)
+        }
+    }));
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads)
+        .build_global()
+        .unwrap();
+    match args.first().map(String::as_str) {
+        Some("render") => render(&args[1..], &config),
+        Some("run") => run(&args[1..], &config),
+        Some("compare") => compare(&args[1..], &config),
+        Some("serve") => serve(&config),
+        Some("validate") => validate(&args[1..], &config),
+        // `config show`: the effective settings after merging files and flags
+        Some("config") if args.get(1).is_some_and(|a| a == "show") => print!("{config}"),
+        Some("config") => eprintln!("usage: config show"),
+        // plain `aoc2023 [day]` is short for `run`
+        _ => run(&args, &config),
+    }
 }
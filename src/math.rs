@@ -0,0 +1,215 @@
+use std::{
+    fmt::{self, Display},
+    ops::Shl,
+    panic,
+};
+
+use num::{traits::CheckedShl, CheckedAdd, CheckedMul, Integer, Zero};
+
+/// GCD of all values; zero for an empty iterator.
+pub fn gcd_all<T: Integer + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), |a, v| a.gcd(&v))
+}
+
+/// LCM of all values; one for an empty iterator.
+pub fn lcm_all<T: Integer + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), |a, v| a.lcm(&v))
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+/// Merges `t ≡ a.0 (mod a.1)` and `t ≡ b.0 (mod b.1)` into a single congruence
+/// modulo their LCM; the moduli need not be coprime.
+pub fn crt(a: (i128, i128), b: (i128, i128)) -> Option<(i128, i128)> {
+    let (g, x, _) = extended_gcd(a.1, b.1);
+    if (b.0 - a.0) % g != 0 {
+        return None;
+    }
+    let m = a.1 / g * b.1;
+    let k = ((b.0 - a.0) / g * x).rem_euclid(b.1 / g);
+    Some(((a.0 + a.1 * k).rem_euclid(m), m))
+}
+
+/// Solves a whole system of `(residue, modulus)` congruences, `None` if inconsistent.
+pub fn crt_all(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences.into_iter().try_fold((0, 1), crt)
+}
+
+/// Exact `floor(sqrt(n))`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above never undershoots, so stop once it stops decreasing
+    let mut x = 1 << (n.ilog2() / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Smallest and largest integers `x` with `a x² + b x + c < 0`, for `a > 0`.
+pub fn quadratic_below_zero(a: i128, b: i128, c: i128) -> Option<(i128, i128)> {
+    let f = |x: i128| (a * x + b) * x + c;
+    let d = b * b - 4 * a * c;
+    if d <= 0 {
+        return None;
+    }
+    // start from the integer-rounded roots and step onto the exact boundaries
+    let s = isqrt(d as u128) as i128;
+    let mut lo = Integer::div_floor(&(-b - s), &(2 * a));
+    let mut hi = Integer::div_floor(&(-b + s), &(2 * a)) + 1;
+    while f(lo) >= 0 && lo <= hi {
+        lo += 1;
+    }
+    while f(lo - 1) < 0 {
+        lo -= 1;
+    }
+    while f(hi) >= 0 && hi >= lo {
+        hi -= 1;
+    }
+    while f(hi + 1) < 0 {
+        hi += 1;
+    }
+    (lo <= hi).then_some((lo, hi))
+}
+
+/// Value at `x` of the lowest-degree polynomial through `(i, values[i])`, built from
+/// Newton forward differences (the same polynomial Lagrange interpolation gives).
+/// `x` may lie outside `0..values.len()`, including negative, to extrapolate.
+/// Overflows are reported naming `what`, like [`add`].
+pub fn extrapolate(values: &[i64], x: i64, what: &str) -> i64 {
+    let mut row: Vec<i128> = values.iter().map(|v| *v as i128).collect();
+    let (x, mut binomial, mut r) = (x as i128, 1i128, 0i128);
+    let mut k = 0;
+    while !row.is_empty() {
+        // C(x, k) * Δᵏ y₀
+        r = add(r, mul(binomial, row[0], what), what);
+        binomial = mul(binomial, x - k, what) / (k + 1);
+        row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        k += 1;
+    }
+    narrow(r, what)
+}
+
+/// Panic payload of an integer overflow, which the runner reports as
+/// `<what>: overflow` instead of a crash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow(pub String);
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// `a + b` in a solver's hot path. With the `checked-math` feature an overflow
+/// unwinds with an [`Overflow`] naming `what`, e.g. `"day4 p2: card copies"`;
+/// without it this is `+`.
+#[inline(always)]
+pub fn add<T: CheckedAdd>(a: T, b: T, what: &str) -> T {
+    if cfg!(feature = "checked-math") {
+        a.checked_add(&b).unwrap_or_else(|| overflow(what))
+    } else {
+        a + b
+    }
+}
+
+/// `a * b`, checked like [`add`].
+#[inline(always)]
+pub fn mul<T: CheckedMul>(a: T, b: T, what: &str) -> T {
+    if cfg!(feature = "checked-math") {
+        a.checked_mul(&b).unwrap_or_else(|| overflow(what))
+    } else {
+        a * b
+    }
+}
+
+/// `a << n`, checked like [`add`], including shifts past the type's width.
+#[inline(always)]
+pub fn shl<T: CheckedShl + Shl<u32, Output = T>>(a: T, n: u32, what: &str) -> T {
+    if cfg!(feature = "checked-math") {
+        a.checked_shl(n).unwrap_or_else(|| overflow(what))
+    } else {
+        a << n
+    }
+}
+
+/// Sum of `values`, checked like [`add`].
+pub fn sum<T: CheckedAdd + Zero>(values: impl IntoIterator<Item = T>, what: &str) -> T {
+    values.into_iter().fold(T::zero(), |a, v| add(a, v, what))
+}
+
+/// Converts `v` to a narrower type, unwinding with an [`Overflow`] naming `what`
+/// if it doesn't fit. Always checked, as a truncated answer is never what anyone wants.
+pub fn narrow<T: TryFrom<U>, U: Copy + Display>(v: U, what: &str) -> T {
+    T::try_from(v).unwrap_or_else(|_| overflow(&format!("{what} ({v} is out of range)")))
+}
+
+#[cold]
+fn overflow(what: &str) -> ! {
+    panic::panic_any(Overflow(format!("{what}: overflow")))
+}
+
+/// The [`Overflow`] that `f` unwound with, as an error; other panics carry on.
+pub fn catch_overflow<R>(f: impl FnOnce() -> R) -> Result<R, Overflow> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).map_err(|e| match e.downcast::<Overflow>() {
+        Ok(o) => *o,
+        Err(e) => panic::resume_unwind(e),
+    })
+}
+
+#[test]
+fn test_math() {
+    assert_eq!(gcd_all([12, 18, 30]), 6);
+    assert_eq!(lcm_all([4u64, 6, 10]), 60);
+    assert_eq!((gcd_all::<u8>([]), lcm_all::<u8>([])), (0, 1));
+
+    let (g, x, y) = extended_gcd(240, 46);
+    assert_eq!((g, 240 * x + 46 * y), (2, 2));
+    assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+    assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+    assert_eq!(crt((0, 4), (1, 6)), None);
+    assert_eq!(crt_all([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+
+    assert_eq!((isqrt(0), isqrt(15), isqrt(16), isqrt(17)), (0, 3, 4, 4));
+    assert_eq!(isqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+
+    // (7 - h) * h > 9 for h in 2..=5
+    assert_eq!(quadratic_below_zero(1, -7, 9), Some((2, 5)));
+    // (30 - h) * h > 200 for h in 11..=19, exact roots 10 and 20 excluded
+    assert_eq!(quadratic_below_zero(1, -30, 200), Some((11, 19)));
+    assert_eq!(quadratic_below_zero(1, -2, 1), None);
+
+    assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], 6, "e"), 18);
+    assert_eq!(extrapolate(&[1, 3, 6, 10, 15, 21], 6, "e"), 28);
+    assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45], -1, "e"), 5);
+    assert_eq!(extrapolate(&[7], 100, "e"), 7);
+    assert_eq!((add(2u8, 3, "a"), mul(4u8, 5, "m")), (5, 20));
+    assert_eq!(sum([u32::MAX as u64, 1], "s"), 1 << 32);
+    assert_eq!(narrow::<u8, _>(255u32, "n"), 255);
+    assert_eq!(shl(1u64, 63, "s"), 1 << 63);
+    assert_eq!(
+        catch_overflow(|| narrow::<i8, _>(128, "day0 p1: n")),
+        Err(Overflow(
+            "day0 p1: n (128 is out of range): overflow".to_string()
+        ))
+    );
+    assert!(panic::catch_unwind(|| catch_overflow(|| panic!("other"))).is_err());
+    #[cfg(feature = "checked-math")]
+    assert_eq!(
+        catch_overflow(|| shl(1u64, 64, "day4 p1: points")),
+        Err(Overflow("day4 p1: points: overflow".to_string()))
+    );
+}
@@ -0,0 +1,55 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Cache for one run of a recursive function. Every `memoized` call gets its own
+/// table, so concurrent solves (e.g. under rayon) never share or leak state.
+pub struct Memo<'f, K, V> {
+    cache: HashMap<K, V>,
+    f: &'f dyn Fn(&mut Memo<'f, K, V>, K) -> V,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<'_, K, V> {
+    /// Value of the function at `key`, computing (and caching) it on first use.
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            return v.clone();
+        }
+        let v = (self.f)(self, key.clone());
+        self.cache.insert(key, v.clone());
+        v
+    }
+}
+
+/// Evaluates the recursive function `f` at `key`. `f` recurses through the
+/// `Memo` it is handed, so each state is computed at most once per call.
+pub fn memoized<K, V>(key: K, f: impl Fn(&mut Memo<K, V>, K) -> V) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    Memo {
+        cache: HashMap::new(),
+        f: &f,
+    }
+    .get(key)
+}
+
+#[test]
+fn test_memo() {
+    let fib = |n: u64| {
+        memoized(n, |m, n| match n {
+            0 | 1 => n,
+            _ => m.get(n - 1) + m.get(n - 2),
+        })
+    };
+    assert_eq!(fib(10), 55);
+    assert_eq!(fib(90), 2880067194370816120);
+    // binomial coefficients keyed by a tuple state
+    let choose = memoized((30u64, 15u64), |m, (n, k)| {
+        if k == 0 || k == n {
+            1u64
+        } else {
+            m.get((n - 1, k - 1)) + m.get((n - 1, k))
+        }
+    });
+    assert_eq!(choose, 155117520);
+}
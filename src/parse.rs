@@ -0,0 +1,123 @@
+use core::fmt;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref INT: Regex = Regex::new(r"[-+]?\d+").unwrap();
+    static ref RECORD: Regex = Regex::new(r"^(\w+)\s*=\s*\(\s*(\w+)\s*,\s*(\w+)\s*\)$").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingDelimiter {
+        text: String,
+        delimiter: &'static str,
+    },
+    InvalidNumber {
+        token: String,
+    },
+    Malformed {
+        text: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDelimiter { text, delimiter } => {
+                write!(f, "missing '{delimiter}' in {text:?}")
+            }
+            Self::InvalidNumber { token } => write!(f, "invalid number {token:?}"),
+            Self::Malformed { text, expected } => write!(f, "expected {expected}, got {text:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Splits input into its blank-line-separated sections.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|s| s.trim_matches('\n'))
+        .filter(|s| !s.is_empty())
+}
+
+/// Splits `s` once at `delimiter`, trimming both halves.
+pub fn split_pair<'a>(
+    s: &'a str,
+    delimiter: &'static str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    s.split_once(delimiter)
+        .map(|(a, b)| (a.trim(), b.trim()))
+        .ok_or_else(|| ParseError::MissingDelimiter {
+            text: s.to_string(),
+            delimiter,
+        })
+}
+
+fn number<T: FromStr>(token: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::InvalidNumber {
+        token: token.to_string(),
+    })
+}
+
+/// Parses whitespace-separated numbers, tolerating repeated spaces.
+pub fn numbers<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    s.split_whitespace().map(number).collect()
+}
+
+/// Extracts every signed integer from arbitrary text, e.g. `"x=-3, y=+7"` gives `[-3, 7]`.
+pub fn ints<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    INT.find_iter(text)
+        .map(|m| number(m.as_str().trim_start_matches('+')))
+        .collect()
+}
+
+/// Parses a `label: numbers` line such as `"seeds: 79 14 55 13"`.
+pub fn labeled_numbers<T: FromStr>(line: &str) -> Result<(&str, Vec<T>), ParseError> {
+    let (label, values) = split_pair(line, ":")?;
+    Ok((label, numbers(values)?))
+}
+
+/// Parses a `key = (a, b)` record such as `"AAA = (BBB, CCC)"`.
+pub fn record(line: &str) -> Result<(&str, (&str, &str)), ParseError> {
+    let c = RECORD
+        .captures(line.trim())
+        .ok_or_else(|| ParseError::Malformed {
+            text: line.to_string(),
+            expected: "`key = (a, b)`",
+        })?;
+    let get = |i| c.get(i).unwrap().as_str();
+    Ok((get(1), (get(2), get(3))))
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(sections("a\nb\n\n\nc\n").collect::<Vec<_>>(), ["a\nb", "c"]);
+    assert_eq!(numbers::<u32>(" 1  2 3"), Ok(vec![1, 2, 3]));
+    assert_eq!(
+        numbers::<u32>("1 x"),
+        Err(ParseError::InvalidNumber {
+            token: "x".to_string()
+        })
+    );
+    assert_eq!(ints::<i32>("x=-3, y=+7 z10"), Ok(vec![-3, 7, 10]));
+    assert!(ints::<u8>("300").is_err());
+    assert_eq!(
+        labeled_numbers::<u64>("seeds: 79 14"),
+        Ok(("seeds", vec![79, 14]))
+    );
+    assert_eq!(
+        labeled_numbers::<u64>("seeds 79"),
+        Err(ParseError::MissingDelimiter {
+            text: "seeds 79".to_string(),
+            delimiter: ":"
+        })
+    );
+    assert_eq!(record("AAA = (BBB, CCC)"), Ok(("AAA", ("BBB", "CCC"))));
+    assert!(record("AAA = BBB, CCC").is_err());
+}
@@ -0,0 +1,70 @@
+use crate::{geometry::Point, math};
+
+/// Simple polygon on the integer lattice, given by its vertices in traversal
+/// order (either orientation). The closing edge back to the first vertex is implied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Self { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Twice the area, by the shoelace formula; always an integer for lattice polygons.
+    pub fn double_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+            .sum::<i64>()
+            .abs()
+    }
+
+    /// Number of lattice points lying on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| math::gcd_all([(b.x - a.x) as i64, (b.y - a.y) as i64]))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, by Pick's theorem `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+}
+
+#[test]
+fn test_polygon() {
+    let square = Polygon::new(
+        [(0, 0), (4, 0), (4, 4), (0, 4)]
+            .map(|(x, y)| Point::new(x, y))
+            .to_vec(),
+    );
+    assert_eq!(square.double_area(), 32);
+    assert_eq!(square.boundary_points(), 16);
+    assert_eq!(square.interior_points(), 9);
+    // L shape, clockwise on screen, with a collinear vertex
+    let l = Polygon::new(
+        [(0, 0), (0, 3), (0, 6), (2, 6), (2, 2), (5, 2), (5, 0)]
+            .map(|(x, y)| Point::new(x, y))
+            .to_vec(),
+    );
+    assert_eq!(l.double_area(), 36);
+    assert_eq!(l.boundary_points(), 22);
+    assert_eq!(l.interior_points(), 8);
+    // diagonal edges contribute gcd(dx, dy) boundary points
+    let triangle = Polygon::new(
+        [(0, 0), (4, 2), (0, 4)]
+            .map(|(x, y)| Point::new(x, y))
+            .to_vec(),
+    );
+    assert_eq!(triangle.double_area(), 16);
+    assert_eq!(triangle.boundary_points(), 8);
+    assert_eq!(triangle.interior_points(), 5);
+}
@@ -0,0 +1,130 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// Receiver of solver progress. Shared by reference across rayon workers, so
+/// reporting only touches atomics.
+pub trait Progress: Sync {
+    /// Announces how many units of work (records, beams, ...) there are.
+    fn start(&self, total: usize);
+
+    /// Records `n` more units done.
+    fn advance(&self, n: usize);
+}
+
+/// Progress reporting disabled; what every solver uses outside of `run`.
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    #[inline(always)]
+    fn start(&self, _total: usize) {}
+
+    #[inline(always)]
+    fn advance(&self, _n: usize) {}
+}
+
+const REDRAW: Duration = Duration::from_millis(100);
+
+/// Progress bar with rate and ETA redrawn in place on stderr, at most every
+/// [`REDRAW`] and only when stderr is a terminal.
+pub struct Bar {
+    label: String,
+    visible: bool,
+    started: Instant,
+    total: AtomicUsize,
+    done: AtomicUsize,
+    // nanoseconds after `started` before the next redraw
+    next_draw: AtomicU64,
+}
+
+impl Bar {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            visible: io::stderr().is_terminal(),
+            started: Instant::now(),
+            total: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+            next_draw: AtomicU64::new(0),
+        }
+    }
+
+    fn line(&self, done: usize, total: usize, elapsed: Duration) -> String {
+        const WIDTH: usize = 30;
+        let frac = if total == 0 {
+            1.0
+        } else {
+            (done as f64 / total as f64).min(1.0)
+        };
+        let filled = (frac * WIDTH as f64) as usize;
+        let rate = done as f64 / elapsed.as_secs_f64().max(1e-9);
+        let eta = if done == 0 {
+            "?".to_string()
+        } else {
+            format!("{:.1}s", total.saturating_sub(done) as f64 / rate)
+        };
+        format!(
+            "{} [{}{}] {done}/{total} {:.0}% {rate:.0}/s ETA {eta}",
+            self.label,
+            "#".repeat(filled),
+            ".".repeat(WIDTH - filled),
+            frac * 100.0
+        )
+    }
+
+    /// Clears the bar once the solver is done.
+    pub fn finish(&self) {
+        if self.visible {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+impl Progress for Bar {
+    fn start(&self, total: usize) {
+        self.total.store(total, Relaxed);
+        self.done.store(0, Relaxed);
+    }
+
+    fn advance(&self, n: usize) {
+        let done = self.done.fetch_add(n, Relaxed) + n;
+        if !self.visible {
+            return;
+        }
+        let now = self.started.elapsed();
+        let next = self.next_draw.load(Relaxed);
+        // whichever worker wins the exchange draws, the others carry on
+        if now.as_nanos() as u64 >= next
+            && self
+                .next_draw
+                .compare_exchange(next, (now + REDRAW).as_nanos() as u64, Relaxed, Relaxed)
+                .is_ok()
+        {
+            let line = self.line(done, self.total.load(Relaxed), now);
+            let mut err = io::stderr().lock();
+            let _ = write!(err, "\r\x1b[2K{line}").and_then(|_| err.flush());
+        }
+    }
+}
+
+#[test]
+fn test_progress() {
+    use rayon::prelude::*;
+
+    let mut bar = Bar::new("day12 p2");
+    bar.visible = false;
+    bar.start(1000);
+    (0..1000).into_par_iter().for_each(|_| bar.advance(1));
+    assert_eq!(bar.done.load(Relaxed), 1000);
+    assert_eq!(
+        bar.line(250, 1000, Duration::from_secs(2)),
+        "day12 p2 [#######.......................] 250/1000 25% 125/s ETA 6.0s"
+    );
+    assert!(bar
+        .line(0, 10, Duration::ZERO)
+        .ends_with("0/10 0% 0/s ETA ?"));
+    NoProgress.start(1);
+    NoProgress.advance(1);
+}
@@ -0,0 +1,239 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use crate::{geometry::Point, grid::Grid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+
+    /// Linear blend from `self` (`t = 0`) to `other` (`t = 1`).
+    pub fn mix(self, other: Self, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let f = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Self(f(self.0, other.0), f(self.1, other.1), f(self.2, other.2))
+    }
+
+    /// Black-red-yellow-white heat scale for `t` in `0..=1`.
+    pub fn heat(t: f64) -> Self {
+        let stops = [
+            Self::BLACK,
+            Self(180, 20, 20),
+            Self(250, 200, 40),
+            Self::WHITE,
+        ];
+        let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (t as usize).min(stops.len() - 2);
+        stops[i].mix(stops[i + 1], t - i as f64)
+    }
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Picture of a puzzle grid, one colored square of `scale` pixels per cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    cells: Grid<Rgb>,
+    scale: usize,
+}
+
+impl Image {
+    /// Colors every cell of `grid` with `color`.
+    pub fn from_grid<T>(grid: &Grid<T>, color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            cells: grid.map(color),
+            scale: 1,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// Draws a layer on top: `cells` are blended towards `color` by `alpha`,
+    /// cells outside the image are ignored.
+    pub fn overlay(
+        mut self,
+        cells: impl IntoIterator<Item = Point>,
+        color: Rgb,
+        alpha: f64,
+    ) -> Self {
+        cells.into_iter().for_each(|p| {
+            if let Some(c) = self.cells.get_mut(p) {
+                *c = c.mix(color, alpha)
+            }
+        });
+        self
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Pixel rows after scaling.
+    fn scanlines(&self) -> impl Iterator<Item = Vec<Rgb>> + '_ {
+        self.cells.rows().flat_map(|row| {
+            let line = row
+                .iter()
+                .flat_map(|c| std::iter::repeat_n(*c, self.scale))
+                .collect::<Vec<_>>();
+            std::iter::repeat_n(line, self.scale)
+        })
+    }
+
+    /// Binary PPM (`P6`).
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (w, h) = (self.width() * self.scale, self.height() * self.scale);
+        let mut r = format!("P6\n{w} {h}\n255\n").into_bytes();
+        self.scanlines()
+            .flatten()
+            .for_each(|c| r.extend([c.0, c.1, c.2]));
+        r
+    }
+
+    /// 8-bit RGB PNG with uncompressed (stored) deflate blocks.
+    pub fn to_png(&self) -> Vec<u8> {
+        let (w, h) = (self.width() * self.scale, self.height() * self.scale);
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend((w as u32).to_be_bytes());
+        ihdr.extend((h as u32).to_be_bytes());
+        // bit depth 8, truecolor, deflate, adaptive filtering, no interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+        let mut raw = Vec::with_capacity(h * (3 * w + 1));
+        self.scanlines().for_each(|line| {
+            raw.push(0);
+            line.iter().for_each(|c| raw.extend([c.0, c.1, c.2]));
+        });
+        let mut r = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut r, b"IHDR", &ihdr);
+        png_chunk(&mut r, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut r, b"IEND", &[]);
+        r
+    }
+
+    /// SVG with one `rect` per horizontal run of same-colored cells.
+    pub fn to_svg(&self) -> String {
+        let s = self.scale;
+        let (w, h) = (self.width() * s, self.height() * s);
+        let mut r = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" shape-rendering=\"crispEdges\">\n"
+        );
+        self.cells.rows().enumerate().for_each(|(y, row)| {
+            let mut x = 0;
+            row.chunk_by(|a, b| a == b).for_each(|run| {
+                writeln!(
+                    r,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{s}\" fill=\"{}\"/>",
+                    x * s,
+                    y * s,
+                    run.len() * s,
+                    run[0].hex()
+                )
+                .unwrap();
+                x += run.len();
+            })
+        });
+        r.push_str("</svg>\n");
+        r
+    }
+
+    /// Writes the image in the format given by the extension of `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let data = match path.extension().and_then(|e| e.to_str()) {
+            Some("png") => self.to_png(),
+            Some("ppm") => self.to_ppm(),
+            Some("svg") => self.to_svg().into_bytes(),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown image format: {}", path.display()),
+                ))
+            }
+        };
+        fs::write(path, data)
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, b| {
+        (0..8).fold(crc ^ *b as u32, |c, _| {
+            if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), d| {
+        let a = (a + *d as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    b << 16 | a
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// zlib stream holding `data` in stored deflate blocks, i.e. without compression.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut r = vec![0x78, 0x01];
+    let blocks = data.chunks(u16::MAX as usize).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        r.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    blocks.iter().enumerate().for_each(|(i, block)| {
+        r.push((i + 1 == blocks.len()) as u8);
+        r.extend((block.len() as u16).to_le_bytes());
+        r.extend((!(block.len() as u16)).to_le_bytes());
+        r.extend(*block);
+    });
+    r.extend(adler32(data).to_be_bytes());
+    r
+}
+
+#[test]
+fn test_render() {
+    assert_eq!(crc32(b"IEND"), 0xae426082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    assert_eq!(Rgb::BLACK.mix(Rgb::WHITE, 0.5), Rgb(128, 128, 128));
+    assert_eq!((Rgb::heat(0.0), Rgb::heat(1.0)), (Rgb::BLACK, Rgb::WHITE));
+
+    let g = Grid::parse("#.\n..\n", |c| c == '#');
+    let img = Image::from_grid(&g, |b| if *b { Rgb::BLACK } else { Rgb::WHITE }).overlay(
+        [Point::new(1, 1), Point::new(5, 5)],
+        Rgb(255, 0, 0),
+        1.0,
+    );
+    assert_eq!(img.cells.get(Point::new(1, 1)), Some(&Rgb(255, 0, 0)));
+    assert_eq!(
+        img.to_ppm(),
+        b"P6\n2 2\n255\n\x00\x00\x00\xff\xff\xff\xff\xff\xff\xff\x00\x00"
+    );
+    let img = img.scale(2);
+    assert_eq!(img.to_ppm().len(), "P6\n4 4\n255\n".len() + 4 * 4 * 3);
+    let png = img.to_png();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[16..24], [0, 0, 0, 4, 0, 0, 0, 4]);
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+    let svg = img.to_svg();
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert!(svg.contains("<rect x=\"0\" y=\"2\" width=\"2\" height=\"2\" fill=\"#ffffff\"/>"));
+}
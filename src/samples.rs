@@ -0,0 +1,32 @@
+//! Tests generated by `build.rs` from the `.ans` sidecars in `src/inputs`, named
+//! `samples::day<N>::<input>_p<part>`, e.g. `cargo test samples::day10::`.
+
+use std::panic;
+
+use itertools::Itertools;
+
+use crate::{answer::Answer, input, variants};
+
+/// Checks every variant registered for the part, the default one first, and
+/// reports all the ones that got it wrong (or panicked) together.
+fn check(day: usize, name: &str, part: usize, expected: &str) {
+    let variants = variants(day, part);
+    assert!(!variants.is_empty(), "(・_・ヾ day{day} is not registered");
+    let input = input::read(name);
+    let expected = Answer::parse(expected);
+    let wrong = variants
+        .iter()
+        .filter_map(|(variant, f)| match panic::catch_unwind(|| f(&input)) {
+            Ok(answer) if answer == expected => None,
+            Ok(answer) => Some(format!("{variant} variant: {answer}")),
+            Err(_) => Some(format!("{variant} variant: panicked")),
+        })
+        .collect_vec();
+    assert!(
+        wrong.is_empty(),
+        "(・_・ヾ expected {expected}, got\n{}",
+        wrong.join("\n")
+    );
+}
+
+include!(concat!(env!("OUT_DIR"), "/samples.rs"));
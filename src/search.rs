@@ -0,0 +1,112 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+/// Cheapest route found by a search: its total cost and every state on it,
+/// starting with one of the start states and ending with the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+struct Node {
+    cost: usize,
+    parent: Option<usize>,
+    closed: bool,
+}
+
+/// A* search; `heuristic` must never overestimate the remaining cost and must be
+/// consistent, otherwise the returned path is not guaranteed to be the cheapest.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut states: Vec<S> = Vec::new();
+    let mut index: HashMap<S, usize> = HashMap::new();
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut open = BinaryHeap::new();
+    starts.into_iter().for_each(|s| {
+        if !index.contains_key(&s) {
+            index.insert(s.clone(), states.len());
+            open.push(Reverse((heuristic(&s), 0, states.len())));
+            nodes.push(Node {
+                cost: 0,
+                parent: None,
+                closed: false,
+            });
+            states.push(s);
+        }
+    });
+    while let Some(Reverse((_, cost, i))) = open.pop() {
+        if nodes[i].closed || cost > nodes[i].cost {
+            continue;
+        }
+        nodes[i].closed = true;
+        if goal(&states[i]) {
+            let mut path = vec![i];
+            while let Some(parent) = nodes[*path.last().unwrap()].parent {
+                path.push(parent);
+            }
+            return Some(Path {
+                cost,
+                states: path.iter().rev().map(|j| states[*j].clone()).collect(),
+            });
+        }
+        let current = states[i].clone();
+        successors(&current).into_iter().for_each(|(s, step)| {
+            let j = *index.entry(s.clone()).or_insert_with(|| {
+                states.push(s);
+                nodes.push(Node {
+                    cost: usize::MAX,
+                    parent: None,
+                    closed: false,
+                });
+                states.len() - 1
+            });
+            let c = cost + step;
+            if !nodes[j].closed && c < nodes[j].cost {
+                nodes[j].cost = c;
+                nodes[j].parent = Some(i);
+                open.push(Reverse((c + heuristic(&states[j]), c, j)));
+            }
+        });
+    }
+    None
+}
+
+#[test]
+fn test_search() {
+    // 0 -1-> 1 -1-> 2 -1-> 4, 0 -5-> 3 -1-> 4, 0 -10-> 4
+    let edges = |n: &u32| -> Vec<(u32, usize)> {
+        match n {
+            0 => vec![(1, 1), (3, 5), (4, 10)],
+            1 => vec![(2, 1)],
+            2 => vec![(4, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    };
+    let expected = Some(Path {
+        cost: 3,
+        states: vec![0, 1, 2, 4],
+    });
+    assert_eq!(astar([0], edges, |_| 0, |n| *n == 4), expected);
+    assert_eq!(
+        astar([0], edges, |n| (4 - *n as usize) / 2, |n| *n == 4),
+        expected
+    );
+    assert_eq!(
+        astar([3, 0], edges, |_| 0, |n| *n == 4).unwrap().states,
+        [3, 4]
+    );
+    assert_eq!(astar([1], edges, |_| 0, |n| *n == 3), None);
+}
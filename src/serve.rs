@@ -0,0 +1,256 @@
+use std::{
+    any::Any,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering::SeqCst},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+
+use crate::{input, json, math, DAYS};
+
+/// Largest request body accepted, answered with 413 beyond that.
+const MAX_BODY: usize = 1 << 20;
+
+/// How long a client may take to send its request or read the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// The request, or the status and message to answer with instead.
+fn read_request(stream: &mut impl Read) -> Result<Request, (u16, String)> {
+    let bad = |e: io::Error| (400, e.to_string());
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(bad)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return Err((400, "bad request line".to_string()));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    let mut len = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(bad)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                len = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| (400, "invalid Content-Length".to_string()))?;
+            }
+        }
+    }
+    if len > MAX_BODY {
+        return Err((413, format!("body larger than {MAX_BODY} bytes")));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).map_err(bad)?;
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn json_error(msg: &str) -> String {
+    format!("{{\"error\":{}}}", json::string(msg))
+}
+
+/// Message of a caught solver panic, which is how the days report bad input.
+fn panic_message(e: Box<dyn Any + Send>) -> String {
+    e.downcast_ref::<String>()
+        .cloned()
+        .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+        .or_else(|| e.downcast_ref::<math::Overflow>().map(|o| o.to_string()))
+        .unwrap_or_else(|| "solver panicked".to_string())
+}
+
+/// Solver threads running, timed out ones included until they finish.
+static SOLVERS: AtomicUsize = AtomicUsize::new(0);
+
+/// A place among at most one solver thread per core, freed when dropped.
+struct Slot;
+
+impl Slot {
+    fn take() -> Option<Self> {
+        let max = thread::available_parallelism().map_or(1, |n| n.get());
+        SOLVERS
+            .fetch_update(SeqCst, SeqCst, |n| (n < max).then_some(n + 1))
+            .ok()
+            .map(|_| Slot)
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        SOLVERS.fetch_sub(1, SeqCst);
+    }
+}
+
+/// Runs both parts of `day` on `input` in a worker thread, giving up after `limit`.
+/// A timed out worker can't be stopped and finishes in the background, keeping
+/// its [`Slot`] so they can't pile up.
+fn solve(day: usize, input: &str, limit: Duration) -> (u16, String) {
+    let Some((_, parts, _)) = DAYS.iter().find(|d| d.0 == day) else {
+        return (404, json_error(&format!("day{day} is not registered")));
+    };
+    let Some(slot) = Slot::take() else {
+        return (503, json_error("all solvers are busy"));
+    };
+    let input = input::normalize(input).0;
+    let parts = *parts;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let results = parts.map(|part| {
+            let now = Instant::now();
+            let r = panic::catch_unwind(AssertUnwindSafe(|| part(&input)));
+            (r.map_err(panic_message), now.elapsed())
+        });
+        drop(slot);
+        // the receiver is gone if the request already timed out
+        let _ = tx.send(results);
+    });
+    let Ok(results) = rx.recv_timeout(limit) else {
+        return (
+            504,
+            json_error(&format!("time limit of {limit:?} exceeded")),
+        );
+    };
+    let parts = results
+        .iter()
+        .enumerate()
+        .map(|(i, (r, time))| {
+            let r = match r {
+                Ok(answer) => format!("\"answer\":{}", answer.to_json()),
+                Err(e) => format!("\"error\":{}", json::string(e)),
+            };
+            format!("\"p{}\":{{{r},\"time_us\":{}}}", i + 1, time.as_micros())
+        })
+        .join(",");
+    (200, format!("{{\"day\":{day},{parts}}}"))
+}
+
+fn route(req: &Request, limit: Duration) -> (u16, String) {
+    let segments = req.path.trim_matches('/').split('/').collect_vec();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => (
+            200,
+            format!("{{\"days\":[{}]}}", DAYS.iter().map(|d| d.0).join(",")),
+        ),
+        ("POST", ["solve", day]) => match day.parse() {
+            Ok(day) => solve(day, &req.body, limit),
+            Err(_) => (404, json_error(&format!("no such day: {day}"))),
+        },
+        (_, ["days"] | ["solve", _]) => (405, json_error("method not allowed")),
+        _ => (404, json_error("not found")),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+fn handle(mut stream: TcpStream, limit: Duration) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let (status, body) = match read_request(&mut stream) {
+        Ok(req) => route(&req, limit),
+        Err((status, msg)) => (status, json_error(&msg)),
+    };
+    let reason = reason(status);
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Answers `GET /days` and `POST /solve/{day}` on `listener`, one thread per
+/// connection, with at most `limit` of solving per request.
+pub fn serve(listener: TcpListener, limit: Duration) {
+    listener.incoming().for_each(|stream| match stream {
+        Ok(stream) => {
+            thread::spawn(move || {
+                if let Err(e) = handle(stream, limit) {
+                    eprintln!("serve: {e}");
+                }
+            });
+        }
+        Err(e) => eprintln!("serve: {e}"),
+    });
+}
+
+#[test]
+fn test_serve() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, Duration::from_secs(10)));
+    let request = |req: String| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(req.as_bytes()).unwrap();
+        let mut r = String::new();
+        stream.read_to_string(&mut r).unwrap();
+        let (head, body) = r.split_once("\r\n\r\n").unwrap();
+        (head.lines().next().unwrap().to_string(), body.to_string())
+    };
+    let post = |path: &str, body: &str| {
+        request(format!(
+            "POST {path} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        ))
+    };
+
+    let (status, body) = request("GET /days HTTP/1.1\r\n\r\n".to_string());
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.starts_with("{\"days\":[1,2,3,"));
+    let (status, body) = post("/solve/9", &input::read("aoc9s.in"));
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.starts_with("{\"day\":9,\"p1\":{\"answer\":114,\"time_us\":"));
+    assert!(body.contains("\"p2\":{\"answer\":2,"));
+    let (_, body) = post("/solve/2", "Game 1: 3 purple\n");
+    assert!(body.contains("\"p1\":{\"error\":\"wrong color: purple\""));
+    assert_eq!(post("/solve/99", "").0, "HTTP/1.1 404 Not Found");
+    assert_eq!(post("/days", "").0, "HTTP/1.1 405 Method Not Allowed");
+    assert_eq!(
+        request("POST /solve/9 HTTP/1.1\r\nContent-Length: 100000000000\r\n\r\n".to_string()).0,
+        "HTTP/1.1 413 Payload Too Large"
+    );
+    assert_eq!(
+        request("POST /solve/9 HTTP/1.1\r\nContent-Length: ten\r\n\r\n".to_string()),
+        (
+            "HTTP/1.1 400 Bad Request".to_string(),
+            json_error("invalid Content-Length")
+        )
+    );
+    assert_eq!(reason(418), "Unknown");
+    let busy = std::iter::from_fn(Slot::take).collect_vec();
+    assert_eq!(solve(9, "", Duration::ZERO).0, 503);
+    drop(busy);
+    assert_eq!(
+        solve(12, &input::read("aoc12.in"), Duration::ZERO),
+        (504, json_error("time limit of 0ns exceeded"))
+    );
+}
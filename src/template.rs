@@ -1,16 +1,18 @@
-use std::fs;
-
-fn solution(input: &str) -> i32 {
-    input.lines().count() as i32
-}
-
-#[test]
-fn test_run() {
-    let input = fs::read_to_string("src/inputs/aocXs.in").unwrap();
-    assert_eq!(solution(&input), 0);
-}
-
-pub fn run() -> (String, String) {
-    let input = fs::read_to_string("src/inputs/aocX.in").unwrap();
-    (solution(&input).to_string(), solution(&input).to_string())
-}
+use crate::answer::Answer;
+
+// Expected answers go next to the inputs, e.g. `src/inputs/aocXs.ans` with
+// `p1: ...` / `p2: ...` lines; the sample tests pick them up automatically.
+
+fn solution(input: &str) -> i32 {
+    input.lines().count() as i32
+}
+
+pub const VERSION: u32 = 1;
+
+pub fn part1(input: &str) -> Answer {
+    solution(input).into()
+}
+
+pub fn part2(_input: &str) -> Answer {
+    Answer::Unsolved("not started".to_string())
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+/// Something wrong with an input, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub col: usize,
+    pub msg: String,
+}
+
+/// Everything a validator found in an input, along with summary stats such as
+/// line counts and grid sizes.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub stats: Vec<(&'static str, String)>,
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    /// Records a problem at 0-based `line` and `col`.
+    pub fn problem(&mut self, line: usize, col: usize, msg: impl Into<String>) {
+        self.problems.push(Problem {
+            line: line + 1,
+            col: col + 1,
+            msg: msg.into(),
+        });
+    }
+
+    pub fn stat(&mut self, name: &'static str, value: impl fmt::Display) {
+        self.stats.push((name, value.to_string()));
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Checks that `input` is a rectangle of characters accepted by `valid`,
+    /// described as `what` in problems, and records its size.
+    pub fn grid(&mut self, input: &str, what: &str, valid: impl Fn(char) -> bool) {
+        let width = input.lines().next().map_or(0, |l| l.chars().count());
+        let mut height = 0;
+        for (i, line) in input.lines().enumerate() {
+            height += 1;
+            let len = line.chars().count();
+            if len != width {
+                self.problem(
+                    i,
+                    len.min(width),
+                    format!("row is {len} wide, expected {width}"),
+                );
+            }
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| !valid(*c))
+                .for_each(|(j, c)| self.problem(i, j, format!("{c:?} is not {what}")));
+        }
+        if height == 0 {
+            self.problem(0, 0, "empty grid");
+        }
+        self.stat("grid", format!("{width}x{height}"));
+    }
+}
+
+/// Stats first, then the problems in input order.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.stats {
+            writeln!(f, "{name}: {value}")?;
+        }
+        for p in self.problems.iter().sorted_by_key(|p| (p.line, p.col)) {
+            writeln!(f, "{}:{}: {}", p.line, p.col, p.msg)?;
+        }
+        match self.problems.len() {
+            0 => writeln!(f, "ok"),
+            n => writeln!(f, "{n} problem(s)"),
+        }
+    }
+}
+
+#[test]
+fn test_validate() {
+    let mut r = Report::default();
+    r.stat("lines", 3);
+    r.grid("123\n1x\n456\n", "a digit", |c| c.is_ascii_digit());
+    assert!(!r.is_ok());
+    assert_eq!(
+        r.to_string(),
+        "lines: 3\ngrid: 3x3\n2:2: 'x' is not a digit\n2:3: row is 2 wide, expected 3\n2 problem(s)\n"
+    );
+    let mut r = Report::default();
+    r.grid("..#\n#..\n", "a rock", |c| ".#".contains(c));
+    assert_eq!(r.to_string(), "grid: 3x2\nok\n");
+    let mut r = Report::default();
+    r.grid("12\na3b\n", "a digit", |c| c.is_ascii_digit());
+    assert_eq!(
+        r.to_string(),
+        "grid: 2x2\n2:1: 'a' is not a digit\n2:3: row is 3 wide, expected 2\n2:3: 'b' is not a digit\n3 problem(s)\n"
+    );
+}